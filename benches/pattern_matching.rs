@@ -0,0 +1,88 @@
+//! Compares the matching engines on a large synthetic binary file, with a
+//! number of patterns similar to what's extracted from big code bases.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+use cpplumber::{MatchingEngine, PatternMatcher};
+
+const DATA_SIZE: usize = 2 * 1024 * 1024;
+const PATTERN_COUNTS: &[usize] = &[1_000, 10_000, 20_000];
+/// Characters commonly found in string literals and symbol names
+const ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyz_%0123456789 ";
+
+/// Simple xorshift generator, so that runs are reproducible
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn next_below(&mut self, max: usize) -> usize {
+        (self.next() % max as u64) as usize
+    }
+}
+
+/// Generates patterns that look like format strings and symbol names, which
+/// mostly start with the same few bytes.
+fn generate_patterns(rng: &mut Rng, count: usize) -> Vec<Vec<u8>> {
+    (0..count)
+        .map(|i| {
+            let prefix: &[u8] = match i % 3 {
+                0 => b"%s: ",
+                1 => b"_Z",
+                _ => b"__",
+            };
+            let mut pattern = prefix.to_vec();
+            let len = 8 + rng.next_below(24);
+            pattern.extend((0..len).map(|_| ALPHABET[rng.next_below(ALPHABET.len())]));
+            pattern
+        })
+        .collect()
+}
+
+/// Generates text-like data, containing some of the patterns.
+fn generate_data(rng: &mut Rng, patterns: &[Vec<u8>]) -> Vec<u8> {
+    let mut data = Vec::with_capacity(DATA_SIZE);
+    while data.len() < DATA_SIZE {
+        if rng.next_below(64) == 0 {
+            data.extend_from_slice(&patterns[rng.next_below(patterns.len())]);
+        } else {
+            data.push(ALPHABET[rng.next_below(ALPHABET.len())]);
+        }
+    }
+    data.truncate(DATA_SIZE);
+    data
+}
+
+fn find_matches(c: &mut Criterion) {
+    let mut group = c.benchmark_group("find_matches");
+    group.sample_size(10);
+    group.throughput(Throughput::Bytes(DATA_SIZE as u64));
+
+    for &pattern_count in PATTERN_COUNTS {
+        let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+        let patterns = generate_patterns(&mut rng, pattern_count);
+        let data = generate_data(&mut rng, &patterns);
+
+        for (engine_name, engine) in [
+            ("aho-corasick", MatchingEngine::AhoCorasick),
+            ("naive", MatchingEngine::Naive),
+        ] {
+            let matcher = PatternMatcher::new(engine, &patterns).expect("new failed");
+            group.bench_with_input(
+                BenchmarkId::new(engine_name, pattern_count),
+                &data,
+                |b, data| b.iter(|| matcher.find_matches(data, 0..data.len())),
+            );
+        }
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, find_matches);
+criterion_main!(benches);
@@ -0,0 +1,334 @@
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fs::{self, File},
+    io::Write,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{compilation_database::CompileCommand, information_leak::PotentialLeak};
+
+/// Version of the cache entries' format. Must be incremented when the format
+/// of `CacheEntry` (or of the types it contains) changes.
+const CACHE_FORMAT_VERSION: u32 = 1;
+
+/// On-disk cache of the artifacts extracted from translation units. Entries are
+/// keyed by source file path and compile arguments, and are only used if the
+/// content of the source file and of all the files it includes is unchanged.
+///
+/// Entries are trusted as is, so the cache's directory must not be writable by
+/// untrusted users (a forged entry could hide leaks).
+pub struct ArtifactCache {
+    directory: PathBuf,
+    /// Description of the options that affect the extraction of artifacts.
+    /// Entries created with different options aren't reused.
+    extraction_settings: String,
+    /// Ignore existing entries, which get replaced by the ones stored during
+    /// this run
+    refresh: bool,
+    /// Digests of the files checked during this run, shared between workers,
+    /// so that files included by many translation units are only hashed once
+    file_digests: Mutex<HashMap<PathBuf, MemoizedDigest>>,
+}
+
+/// Digest of a file, computed on first use (`Some(None)` if the file couldn't
+/// be read)
+type MemoizedDigest = Arc<Mutex<Option<Option<String>>>>;
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry<'l> {
+    /// Files the translation unit depends on (i.e., the source file and the
+    /// files it includes), with their content's digest
+    dependencies: Vec<FileDigest>,
+    potential_leaks: Cow<'l, [PotentialLeak]>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct FileDigest {
+    path: PathBuf,
+    digest: String,
+}
+
+impl ArtifactCache {
+    pub fn new(directory: &Path, extraction_settings: String) -> Result<Self> {
+        fs::create_dir_all(directory).with_context(|| {
+            format!("Failed to create cache directory '{}'", directory.display())
+        })?;
+
+        Ok(Self {
+            directory: directory.to_path_buf(),
+            extraction_settings,
+            refresh: false,
+            file_digests: Mutex::default(),
+        })
+    }
+
+    /// Makes the cache ignore existing entries. Artifacts are still stored, so
+    /// the cache is refreshed for later runs.
+    pub fn refresh(mut self) -> Self {
+        self.refresh = true;
+        self
+    }
+
+    /// Returns the cached artifacts for the given compile command, if they're
+    /// still valid.
+    pub fn load(&self, compile_cmd: &CompileCommand) -> Option<Vec<PotentialLeak>> {
+        if self.refresh {
+            return None;
+        }
+        let entry_data = fs::read(self.entry_path(compile_cmd)).ok()?;
+        let entry: CacheEntry = match serde_json::from_slice(&entry_data) {
+            Ok(entry) => entry,
+            Err(err) => {
+                log::warn!(
+                    "Invalid cache entry for '{}', ignoring it: {}",
+                    compile_cmd.filename.display(),
+                    err
+                );
+                return None;
+            }
+        };
+
+        // Check that none of the dependencies changed
+        let is_up_to_date = entry.dependencies.iter().all(|dependency| {
+            self.file_digest(&dependency.path).as_ref() == Some(&dependency.digest)
+        });
+        if is_up_to_date {
+            Some(entry.potential_leaks.into_owned())
+        } else {
+            None
+        }
+    }
+
+    /// Stores the artifacts extracted for the given compile command, as well as
+    /// the digest of the files they depend on.
+    pub fn store(
+        &self,
+        compile_cmd: &CompileCommand,
+        dependencies: &[PathBuf],
+        potential_leaks: &[PotentialLeak],
+    ) -> Result<()> {
+        let dependencies = dependencies
+            .iter()
+            .map(|path| {
+                Ok(FileDigest {
+                    path: path.clone(),
+                    digest: self
+                        .file_digest(path)
+                        .with_context(|| format!("Failed to read '{}'", path.display()))?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let entry_data = serde_json::to_vec(&CacheEntry {
+            dependencies,
+            potential_leaks: Cow::Borrowed(potential_leaks),
+        })?;
+
+        // Write the entry to a temporary file first, so that concurrent runs
+        // never read partially written entries
+        let mut entry_file = tempfile::NamedTempFile::new_in(&self.directory)?;
+        entry_file.write_all(&entry_data)?;
+        entry_file.persist(self.entry_path(compile_cmd))?;
+
+        Ok(())
+    }
+
+    /// Returns the digest of the given file, or `None` if it cannot be read.
+    /// Files are assumed not to change during a run, so each file is only
+    /// hashed once.
+    fn file_digest(&self, path: &Path) -> Option<String> {
+        let memoized_digest = self
+            .file_digests
+            .lock()
+            .unwrap()
+            .entry(path.to_path_buf())
+            .or_default()
+            .clone();
+        // Other workers needing the same file wait for the digest to be
+        // computed, instead of hashing the file again
+        let mut digest = memoized_digest.lock().unwrap();
+        digest
+            .get_or_insert_with(|| compute_file_digest(path).ok())
+            .clone()
+    }
+
+    fn entry_path(&self, compile_cmd: &CompileCommand) -> PathBuf {
+        let mut hasher = Sha256::new();
+        hasher.update(CACHE_FORMAT_VERSION.to_le_bytes());
+        hasher.update(self.extraction_settings.as_bytes());
+        // Relative paths in arguments depend on the current directory
+        if let Ok(current_dir) = std::env::current_dir() {
+            hasher.update(current_dir.to_string_lossy().as_bytes());
+        }
+        hasher.update([0]);
+        hasher.update(compile_cmd.filename.to_string_lossy().as_bytes());
+        for argument in compile_cmd.arguments.iter() {
+            hasher.update([0]);
+            hasher.update(argument.as_bytes());
+        }
+
+        self.directory.join(format!("{:x}.json", hasher.finalize()))
+    }
+}
+
+/// Returns the SHA-256 digest of the given file's content, as a hexadecimal
+/// string.
+fn compute_file_digest(file_path: &Path) -> Result<String> {
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut File::open(file_path)?, &mut hasher)?;
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::information_leak::{LeakedDataType, SourceLocation};
+
+    fn test_compile_command(source_file_path: &Path) -> CompileCommand {
+        CompileCommand {
+            filename: source_file_path.to_path_buf(),
+            arguments: Arc::new(vec!["-DDEF_TEST".to_string()]),
+        }
+    }
+
+    fn test_potential_leaks(source_file_path: &Path) -> Vec<PotentialLeak> {
+        vec![PotentialLeak {
+            data_type: LeakedDataType::StringLiteral,
+            data: Arc::new("c_string".to_string()),
+            bytes: b"c_string".to_vec(),
+            declaration_metadata: Arc::new(SourceLocation {
+                file: source_file_path.to_path_buf(),
+                line: 3,
+                columns: None,
+            }),
+            alternative_forms: vec![],
+        }]
+    }
+
+    #[test]
+    fn load_stored_artifacts() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let source_file_path = tmp_dir.path().join("main.cc");
+        let header_file_path = tmp_dir.path().join("header.h");
+        fs::write(&source_file_path, "#include \"header.h\"").unwrap();
+        fs::write(&header_file_path, "const char* s = \"c_string\";").unwrap();
+        let cache = ArtifactCache::new(&tmp_dir.path().join("cache"), String::new())
+            .expect("ArtifactCache::new failed");
+        let compile_cmd = test_compile_command(&source_file_path);
+
+        assert!(cache.load(&compile_cmd).is_none());
+        cache
+            .store(
+                &compile_cmd,
+                &[source_file_path.clone(), header_file_path.clone()],
+                &test_potential_leaks(&header_file_path),
+            )
+            .expect("store failed");
+
+        let potential_leaks = cache.load(&compile_cmd).expect("load failed");
+        assert_eq!(potential_leaks.len(), 1);
+        assert_eq!(*potential_leaks[0].data, "c_string");
+        assert_eq!(potential_leaks[0].bytes, b"c_string");
+        assert_eq!(
+            *potential_leaks[0].declaration_metadata,
+            SourceLocation {
+                file: header_file_path,
+                line: 3,
+                columns: None,
+            }
+        );
+    }
+
+    #[test]
+    fn load_outdated_artifacts() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let source_file_path = tmp_dir.path().join("main.cc");
+        let header_file_path = tmp_dir.path().join("header.h");
+        fs::write(&source_file_path, "#include \"header.h\"").unwrap();
+        fs::write(&header_file_path, "const char* s = \"c_string\";").unwrap();
+        let cache = ArtifactCache::new(&tmp_dir.path().join("cache"), String::new())
+            .expect("ArtifactCache::new failed");
+        let compile_cmd = test_compile_command(&source_file_path);
+        cache
+            .store(
+                &compile_cmd,
+                &[source_file_path.clone(), header_file_path.clone()],
+                &test_potential_leaks(&header_file_path),
+            )
+            .expect("store failed");
+
+        // Included files are checked too (files are only hashed once per run,
+        // so changes are only noticed by later runs)
+        fs::write(&header_file_path, "const char* s = \"other\";").unwrap();
+        assert!(cache.load(&compile_cmd).is_some());
+        let next_run_cache = ArtifactCache::new(&tmp_dir.path().join("cache"), String::new())
+            .expect("ArtifactCache::new failed");
+        assert!(next_run_cache.load(&compile_cmd).is_none());
+    }
+
+    #[test]
+    fn load_artifacts_with_different_settings() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let source_file_path = tmp_dir.path().join("main.cc");
+        fs::write(&source_file_path, "const char* s = \"c_string\";").unwrap();
+        let cache_dir_path = tmp_dir.path().join("cache");
+        let cache = ArtifactCache::new(&cache_dir_path, "settings1".to_string())
+            .expect("ArtifactCache::new failed");
+        let compile_cmd = test_compile_command(&source_file_path);
+        cache
+            .store(
+                &compile_cmd,
+                std::slice::from_ref(&source_file_path),
+                &test_potential_leaks(&source_file_path),
+            )
+            .expect("store failed");
+
+        // Different compile arguments
+        let mut other_compile_cmd = test_compile_command(&source_file_path);
+        other_compile_cmd.arguments = Arc::new(vec![]);
+        assert!(cache.load(&other_compile_cmd).is_none());
+        // Different extraction settings
+        let other_cache = ArtifactCache::new(&cache_dir_path, "settings2".to_string())
+            .expect("ArtifactCache::new failed");
+        assert!(other_cache.load(&compile_cmd).is_none());
+    }
+
+    #[test]
+    fn load_artifacts_from_refreshed_cache() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let source_file_path = tmp_dir.path().join("main.cc");
+        fs::write(&source_file_path, "const char* s = \"c_string\";").unwrap();
+        let cache_dir_path = tmp_dir.path().join("cache");
+        let compile_cmd = test_compile_command(&source_file_path);
+        let cache =
+            ArtifactCache::new(&cache_dir_path, String::new()).expect("ArtifactCache::new failed");
+        cache
+            .store(
+                &compile_cmd,
+                std::slice::from_ref(&source_file_path),
+                &test_potential_leaks(&source_file_path),
+            )
+            .expect("store failed");
+
+        // Existing entries are ignored, but new ones are stored
+        let refreshed_cache = ArtifactCache::new(&cache_dir_path, String::new())
+            .expect("ArtifactCache::new failed")
+            .refresh();
+        assert!(refreshed_cache.load(&compile_cmd).is_none());
+        refreshed_cache
+            .store(&compile_cmd, std::slice::from_ref(&source_file_path), &[])
+            .expect("store failed");
+        let next_run_cache =
+            ArtifactCache::new(&cache_dir_path, String::new()).expect("ArtifactCache::new failed");
+        assert_eq!(
+            next_run_cache.load(&compile_cmd).map(|leaks| leaks.len()),
+            Some(0)
+        );
+    }
+}
@@ -0,0 +1,149 @@
+use std::{
+    borrow::Cow,
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::information_leak::PotentialLeak;
+
+/// Version of the artifact files' format. Must be incremented when the format
+/// of `ArtifactFile` (or of the types it contains) changes.
+pub const ARTIFACT_FILE_FORMAT_VERSION: u32 = 1;
+/// Oldest format version that can still be read (fields added since then have
+/// default values)
+const MIN_ARTIFACT_FILE_FORMAT_VERSION: u32 = 1;
+const PKG_NAME: &str = env!("CARGO_PKG_NAME");
+const PKG_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Artifacts extracted from source files, stored so that leaks can be looked
+/// for later (possibly on another machine).
+#[derive(Serialize, Deserialize)]
+struct ArtifactFile<'l> {
+    format_version: u32,
+    /// Name and version of the tool that generated the file
+    generator: String,
+    potential_leaks: Cow<'l, [PotentialLeak]>,
+}
+
+/// Only contains the format version, to be able to check it before parsing the
+/// rest of the file.
+#[derive(Deserialize)]
+struct ArtifactFileHeader {
+    format_version: u32,
+}
+
+/// Saves artifacts into an artifact file.
+pub fn write_artifact_file(file_path: &Path, potential_leaks: &[PotentialLeak]) -> Result<()> {
+    let mut writer = BufWriter::new(File::create(file_path)?);
+    serde_json::to_writer(
+        &mut writer,
+        &ArtifactFile {
+            format_version: ARTIFACT_FILE_FORMAT_VERSION,
+            generator: format!("{} {}", PKG_NAME, PKG_VERSION),
+            potential_leaks: Cow::Borrowed(potential_leaks),
+        },
+    )?;
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// Loads artifacts from an artifact file.
+pub fn read_artifact_file(file_path: &Path) -> Result<Vec<PotentialLeak>> {
+    let artifact_file_data = fs::read(file_path)?;
+    let header: ArtifactFileHeader =
+        serde_json::from_slice(&artifact_file_data).with_context(|| "Invalid artifact file")?;
+    if !(MIN_ARTIFACT_FILE_FORMAT_VERSION..=ARTIFACT_FILE_FORMAT_VERSION)
+        .contains(&header.format_version)
+    {
+        return Err(anyhow!(
+            "Unsupported artifact file format version {} (expected {} to {})",
+            header.format_version,
+            MIN_ARTIFACT_FILE_FORMAT_VERSION,
+            ARTIFACT_FILE_FORMAT_VERSION
+        ));
+    }
+
+    let artifact_file: ArtifactFile =
+        serde_json::from_slice(&artifact_file_data).with_context(|| "Invalid artifact file")?;
+
+    Ok(artifact_file.potential_leaks.into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::PathBuf, sync::Arc};
+
+    use super::*;
+    use crate::information_leak::{LeakedDataType, SourceLocation};
+
+    #[test]
+    fn read_written_artifact_file() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let file_path = tmp_dir.path().join("artifacts.json");
+        let potential_leaks = vec![
+            PotentialLeak {
+                data_type: LeakedDataType::StringLiteral,
+                data: Arc::new("wide_string".to_string()),
+                bytes: "wide_string"
+                    .encode_utf16()
+                    .flat_map(|c| c.to_le_bytes())
+                    .collect(),
+                declaration_metadata: Arc::new(SourceLocation {
+                    file: PathBuf::from("main.cc"),
+                    line: 4,
+                    columns: None,
+                }),
+                alternative_forms: vec![],
+            },
+            PotentialLeak {
+                data_type: LeakedDataType::ClassName,
+                data: Arc::new("MyClass".to_string()),
+                bytes: b"MyClass".to_vec(),
+                declaration_metadata: Arc::new(SourceLocation {
+                    file: PathBuf::from("main.cc"),
+                    line: 10,
+                    columns: None,
+                }),
+                alternative_forms: vec![],
+            },
+        ];
+
+        write_artifact_file(&file_path, &potential_leaks).expect("write_artifact_file failed");
+        let read_potential_leaks =
+            read_artifact_file(&file_path).expect("read_artifact_file failed");
+
+        assert_eq!(read_potential_leaks.len(), potential_leaks.len());
+        for (read_leak, leak) in read_potential_leaks.iter().zip(&potential_leaks) {
+            assert_eq!(read_leak.data, leak.data);
+            assert_eq!(read_leak.bytes, leak.bytes);
+            assert_eq!(read_leak.declaration_metadata, leak.declaration_metadata);
+        }
+    }
+
+    #[test]
+    fn read_unsupported_artifact_file() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let file_path = tmp_dir.path().join("artifacts.json");
+        fs::write(
+            &file_path,
+            r#"{"format_version":999,"generator":"","potential_leaks":[]}"#,
+        )
+        .unwrap();
+
+        assert!(read_artifact_file(&file_path).is_err());
+    }
+
+    #[test]
+    fn read_invalid_artifact_file() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let file_path = tmp_dir.path().join("artifacts.json");
+        fs::write(&file_path, "not an artifact file").unwrap();
+
+        assert!(read_artifact_file(&file_path).is_err());
+    }
+}
@@ -0,0 +1,140 @@
+use std::{collections::HashSet, path::Path, path::PathBuf, sync::Arc};
+
+use anyhow::Result;
+
+use crate::{
+    information_leak::{ConfirmedLeak, LeakedDataType},
+    reporting::read_json_report,
+};
+
+/// Leaks that are already known, and shouldn't be reported again. Leaks are
+/// identified by their value, type and source file rather than by their
+/// location in binary files, which changes with every build.
+#[derive(Default)]
+pub struct Baseline {
+    known_leaks: HashSet<BaselineKey>,
+}
+
+#[derive(PartialEq, Eq, Hash)]
+struct BaselineKey {
+    data_type: LeakedDataType,
+    data: Arc<String>,
+    source_file: PathBuf,
+}
+
+impl From<&ConfirmedLeak> for BaselineKey {
+    fn from(leak: &ConfirmedLeak) -> Self {
+        Self {
+            data_type: leak.data_type,
+            data: leak.data.clone(),
+            source_file: leak.location.source.file.clone(),
+        }
+    }
+}
+
+impl Baseline {
+    /// Loads a baseline from a previous JSON report.
+    pub fn from_json_report(report_path: &Path) -> Result<Self> {
+        Ok(read_json_report(report_path)?.iter().collect())
+    }
+
+    /// Returns true if `leak` is part of the baseline.
+    pub fn contains(&self, leak: &ConfirmedLeak) -> bool {
+        self.known_leaks.contains(&BaselineKey::from(leak))
+    }
+
+    /// Returns the number of distinct leaks in the baseline.
+    pub fn len(&self) -> usize {
+        self.known_leaks.len()
+    }
+
+    /// Returns true if the baseline doesn't contain any leak.
+    pub fn is_empty(&self) -> bool {
+        self.known_leaks.is_empty()
+    }
+}
+
+impl<'l> FromIterator<&'l ConfirmedLeak> for Baseline {
+    fn from_iter<I: IntoIterator<Item = &'l ConfirmedLeak>>(leaks: I) -> Self {
+        Self {
+            known_leaks: leaks.into_iter().map(BaselineKey::from).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        information_leak::ConfirmedLeakWithUniqueLocation,
+        reporting::{dump_confirmed_leaks, ReportFormat, ReportOptions},
+    };
+
+    #[test]
+    fn baseline_from_json_report() {
+        let mut report_file = tempfile::NamedTempFile::new().unwrap();
+        dump_confirmed_leaks(
+            &mut report_file,
+            vec![
+                ConfirmedLeakWithUniqueLocation::from(ConfirmedLeak::new(
+                    LeakedDataType::StringLiteral,
+                    "known_secret",
+                    "main.cc",
+                    1,
+                    "a.out",
+                    0x100,
+                )),
+                ConfirmedLeakWithUniqueLocation::from(ConfirmedLeak::new(
+                    LeakedDataType::StringLiteral,
+                    "known_secret",
+                    "main.cc",
+                    1,
+                    "a.out",
+                    0x200,
+                )),
+            ],
+            &ReportOptions {
+                format: ReportFormat::Json,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let baseline = Baseline::from_json_report(report_file.path()).unwrap();
+        assert_eq!(baseline.len(), 1);
+        // Offsets in binary files are ignored
+        assert!(baseline.contains(&ConfirmedLeak::new(
+            LeakedDataType::StringLiteral,
+            "known_secret",
+            "main.cc",
+            1,
+            "a.out",
+            0x300,
+        )));
+        // Other values, types and source files are new leaks
+        assert!(!baseline.contains(&ConfirmedLeak::new(
+            LeakedDataType::StringLiteral,
+            "new_secret",
+            "main.cc",
+            1,
+            "a.out",
+            0x100,
+        )));
+        assert!(!baseline.contains(&ConfirmedLeak::new(
+            LeakedDataType::StructName,
+            "known_secret",
+            "main.cc",
+            1,
+            "a.out",
+            0x100,
+        )));
+        assert!(!baseline.contains(&ConfirmedLeak::new(
+            LeakedDataType::StringLiteral,
+            "known_secret",
+            "other.cc",
+            1,
+            "a.out",
+            0x100,
+        )));
+    }
+}
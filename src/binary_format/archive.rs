@@ -0,0 +1,34 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use object::read::archive::ArchiveFile;
+
+use super::{BinaryFileLayout, BinaryRegion};
+
+/// Extracts the members of an archive (GNU, BSD or MSVC `.lib` format), as well
+/// as the layout of each member.
+pub fn parse_archive_layout(data: &[u8]) -> Result<BinaryFileLayout> {
+    let archive = ArchiveFile::parse(data)?;
+
+    let mut layout = BinaryFileLayout::default();
+    for member in archive.members() {
+        let member = member?;
+        let member_name = Arc::new(String::from_utf8_lossy(member.name()).into_owned());
+        let (file_offset, file_size) = member.file_range();
+
+        // Members that cannot be parsed are still scanned, but without section
+        // information. Note: Members are only 2-byte aligned in archives, they
+        // can be parsed in place thanks to `object`'s "unaligned" feature.
+        match BinaryFileLayout::parse(member.data(data)?) {
+            Ok(member_layout) => layout.merge(member_layout.shifted(file_offset)),
+            Err(err) => log::warn!("Failed to parse archive member '{}': {}", member_name, err),
+        }
+        layout.members.push(BinaryRegion {
+            name: member_name,
+            file_offset,
+            file_size,
+        });
+    }
+
+    Ok(layout)
+}
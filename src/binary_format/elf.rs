@@ -0,0 +1,66 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use object::{
+    elf::{ET_REL, PT_LOAD, SHF_ALLOC},
+    read::elf::{FileHeader, ProgramHeader, SectionHeader},
+    Endianness,
+};
+
+use super::{BinaryFileLayout, BinarySection};
+
+/// Extracts sections (and the `PT_LOAD` segments they belong to) from an ELF
+/// file's headers.
+pub fn parse_elf_layout<Elf: FileHeader<Endian = Endianness>>(
+    data: &[u8],
+) -> Result<BinaryFileLayout> {
+    let header = Elf::parse(data)?;
+    let endian = header.endian()?;
+    let program_headers = header.program_headers(endian, data)?;
+    let section_table = header.sections(endian, data)?;
+    // Relocatable object files (e.g., archive members) aren't loaded as is, so
+    // their sections don't have meaningful addresses
+    let is_relocatable = header.e_type(endian) == ET_REL;
+
+    let sections = section_table
+        .iter()
+        .filter_map(|section| {
+            // Sections without data in the file (e.g., ".bss") are ignored
+            let (file_offset, file_size) = section.file_range(endian)?;
+            if file_size == 0 {
+                return None;
+            }
+            let name = section_table.section_name(endian, section).ok()?;
+
+            // Only sections with the `SHF_ALLOC` flag are loaded in memory
+            let is_loaded = section.sh_flags(endian).into() & u64::from(SHF_ALLOC) != 0;
+            let segment = if is_loaded {
+                program_headers
+                    .iter()
+                    .enumerate()
+                    .find(|(_, segment)| {
+                        let segment_offset: u64 = segment.p_offset(endian).into();
+                        let segment_size: u64 = segment.p_filesz(endian).into();
+                        segment.p_type(endian) == PT_LOAD
+                            && segment_offset <= file_offset
+                            && file_offset < segment_offset + segment_size
+                    })
+                    .map(|(index, _)| Arc::new(format!("LOAD[{}]", index)))
+            } else {
+                None
+            };
+
+            Some(BinarySection {
+                name: Arc::new(String::from_utf8_lossy(name).into_owned()),
+                segment,
+                file_offset,
+                file_size,
+                virtual_address: (is_loaded && !is_relocatable)
+                    .then(|| section.sh_addr(endian).into()),
+                relative_virtual_address: None,
+            })
+        })
+        .collect();
+
+    Ok(BinaryFileLayout::new(sections, vec![]))
+}
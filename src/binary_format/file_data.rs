@@ -0,0 +1,82 @@
+use std::{fs::File, io::Read, ops::Deref, path::Path};
+
+use anyhow::Result;
+use memmap2::Mmap;
+
+/// Content of a binary file. Files are memory-mapped when possible, so that
+/// huge files (e.g., firmware images or debug builds) don't have to be loaded
+/// in memory.
+pub enum BinaryFileData {
+    Mapped(Mmap),
+    Loaded(Vec<u8>),
+}
+
+impl BinaryFileData {
+    /// Maps the given file in memory, or reads its content if it cannot be
+    /// mapped.
+    pub fn open(file_path: &Path) -> Result<Self> {
+        let mut file = File::open(file_path)?;
+        // Empty files cannot be mapped on some platforms
+        if file.metadata()?.len() == 0 {
+            return Ok(Self::Loaded(vec![]));
+        }
+
+        // SAFETY: The mapping is only read from. Its content is undefined if
+        // the file is modified while it's being scanned, which is the same
+        // for any other way of reading the file.
+        match unsafe { Mmap::map(&file) } {
+            Ok(mapping) => Ok(Self::Mapped(mapping)),
+            Err(err) => {
+                log::warn!(
+                    "Failed to map '{}' in memory ({}), reading it instead",
+                    file_path.display(),
+                    err
+                );
+                let mut data = vec![];
+                file.read_to_end(&mut data)?;
+                Ok(Self::Loaded(data))
+            }
+        }
+    }
+}
+
+impl Deref for BinaryFileData {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Self::Mapped(mapping) => mapping,
+            Self::Loaded(data) => data,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use super::*;
+
+    #[test]
+    fn open_maps_file() {
+        let file_path =
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data/main/file_list_proj/a.out");
+        let data = BinaryFileData::open(&file_path).expect("open failed");
+
+        assert!(matches!(data, BinaryFileData::Mapped(_)));
+        assert_eq!(&data[..], &fs::read(file_path).unwrap()[..]);
+    }
+
+    #[test]
+    fn open_empty_file() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let data = BinaryFileData::open(file.path()).expect("open failed");
+
+        assert!(data.is_empty());
+    }
+
+    #[test]
+    fn open_invalid_path() {
+        assert!(BinaryFileData::open(Path::new("does/not/exist")).is_err());
+    }
+}
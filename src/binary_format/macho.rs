@@ -0,0 +1,100 @@
+use std::sync::Arc;
+
+use anyhow::{anyhow, Context, Result};
+use object::{
+    macho::{
+        CPU_SUBTYPE_ARM64E, CPU_SUBTYPE_MASK, CPU_TYPE_ARM, CPU_TYPE_ARM64, CPU_TYPE_ARM64_32,
+        CPU_TYPE_POWERPC, CPU_TYPE_POWERPC64, CPU_TYPE_X86, CPU_TYPE_X86_64, MH_OBJECT,
+    },
+    read::macho::{FatArch, MachHeader, Section, Segment},
+    Endianness,
+};
+
+use super::{BinaryFileLayout, BinaryRegion, BinarySection};
+
+/// Extracts segments and sections from a Mach-O file's load commands.
+pub fn parse_macho_layout<Mach: MachHeader<Endian = Endianness>>(
+    data: &[u8],
+) -> Result<BinaryFileLayout> {
+    let header = Mach::parse(data, 0)?;
+    let endian = header.endian()?;
+    // Sections of object files aren't loaded at their address
+    let is_relocatable = header.filetype(endian) == MH_OBJECT;
+
+    let mut sections = vec![];
+    let mut commands = header.load_commands(endian, data, 0)?;
+    while let Some(command) = commands.next()? {
+        if let Some((segment, section_data)) = Mach::Segment::from_command(command)? {
+            let segment_name = Arc::new(String::from_utf8_lossy(segment.name()).into_owned());
+            for section in segment.sections(endian, section_data)? {
+                // Sections without data in the file (e.g., "__bss") are ignored
+                let (file_offset, file_size) = match section.file_range(endian) {
+                    Some(range) if range.1 != 0 => range,
+                    _ => continue,
+                };
+
+                sections.push(BinarySection {
+                    // Sections are identified as "segment,section"
+                    name: Arc::new(format!(
+                        "{},{}",
+                        segment_name,
+                        String::from_utf8_lossy(section.name())
+                    )),
+                    segment: Some(segment_name.clone()),
+                    file_offset,
+                    file_size,
+                    virtual_address: (!is_relocatable).then(|| section.addr(endian).into()),
+                    relative_virtual_address: None,
+                });
+            }
+        }
+    }
+
+    Ok(BinaryFileLayout::new(sections, vec![]))
+}
+
+/// Extracts the architecture slices of a universal binary, as well as the
+/// layout of the file contained in each slice (usually a Mach-O image or an
+/// archive).
+pub fn parse_fat_layout<Arch: FatArch>(data: &[u8], arches: &[Arch]) -> Result<BinaryFileLayout> {
+    let mut layout = BinaryFileLayout::default();
+    for arch in arches {
+        let architecture = Arc::new(architecture_name(arch.cputype(), arch.cpusubtype()));
+        let (file_offset, file_size) = arch.file_range();
+        // Offsets contained in Mach-O headers are relative to the start of the
+        // slice, so we work on the slice's data directly
+        let slice_data = file_offset
+            .checked_add(file_size)
+            .and_then(|file_end| data.get(file_offset as usize..file_end as usize))
+            .ok_or_else(|| anyhow!("Invalid '{}' slice range", architecture))?;
+        let slice_layout = BinaryFileLayout::parse(slice_data)
+            .with_context(|| format!("Failed to parse '{}' slice", architecture))?;
+
+        layout.slices.push(BinaryRegion {
+            name: architecture,
+            file_offset,
+            file_size,
+        });
+        layout.merge(slice_layout.shifted(file_offset));
+    }
+
+    Ok(layout)
+}
+
+/// Returns the name commonly used to designate the given CPU type (e.g., the
+/// one used by `lipo`).
+fn architecture_name(cputype: u32, cpusubtype: u32) -> String {
+    match cputype {
+        CPU_TYPE_X86 => "i386".to_string(),
+        CPU_TYPE_X86_64 => "x86_64".to_string(),
+        CPU_TYPE_ARM => "arm".to_string(),
+        CPU_TYPE_ARM64 if cpusubtype & !CPU_SUBTYPE_MASK == CPU_SUBTYPE_ARM64E => {
+            "arm64e".to_string()
+        }
+        CPU_TYPE_ARM64 => "arm64".to_string(),
+        CPU_TYPE_ARM64_32 => "arm64_32".to_string(),
+        CPU_TYPE_POWERPC => "ppc".to_string(),
+        CPU_TYPE_POWERPC64 => "ppc64".to_string(),
+        _ => format!("cputype {}", cputype),
+    }
+}
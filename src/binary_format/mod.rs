@@ -0,0 +1,703 @@
+mod archive;
+mod elf;
+mod file_data;
+mod macho;
+mod pe;
+mod section_filter;
+
+use std::{ops::Range, sync::Arc};
+
+use anyhow::Result;
+use object::{read::macho::FatHeader, FileKind};
+
+pub use file_data::BinaryFileData;
+pub use section_filter::SectionFilter;
+
+/// Struct containing information on a section of a binary file, as described
+/// by the file's headers.
+#[derive(Debug)]
+pub struct BinarySection {
+    /// Name of the section (e.g., ".rodata")
+    pub name: Arc<String>,
+    /// Name of the segment the section is loaded with, if any
+    pub segment: Option<Arc<String>>,
+    /// Offset of the section's data in the file
+    pub file_offset: u64,
+    /// Size of the section's data in the file
+    pub file_size: u64,
+    /// Virtual address at which the section is loaded, if it is loaded
+    pub virtual_address: Option<u64>,
+    /// Address at which the section is loaded, relative to the image's base
+    /// address (only relevant for PE files)
+    pub relative_virtual_address: Option<u64>,
+}
+
+/// Struct describing a range of a binary file that contains a specific kind of
+/// data (e.g., PE resources, Mach-O architecture slices).
+#[derive(Debug)]
+pub struct BinaryRegion {
+    /// Description of the data contained in the region
+    pub name: Arc<String>,
+    /// Offset of the region in the file
+    pub file_offset: u64,
+    /// Size of the region in the file
+    pub file_size: u64,
+}
+
+impl BinaryRegion {
+    fn file_range(&self) -> Range<u64> {
+        self.file_offset..self.file_offset + self.file_size
+    }
+
+    fn contains(&self, file_offset: u64) -> bool {
+        self.file_range().contains(&file_offset)
+    }
+}
+
+/// Struct containing information on where a given file offset is located,
+/// from the binary format's point of view.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct BinaryFileLocation {
+    /// Architecture of the slice containing the data (universal binaries only)
+    pub architecture: Option<Arc<String>>,
+    /// Name of the archive member containing the data (archives only)
+    pub archive_member: Option<Arc<String>>,
+    pub section: Option<Arc<String>>,
+    pub segment: Option<Arc<String>>,
+    pub virtual_address: Option<u64>,
+    pub relative_virtual_address: Option<u64>,
+    /// Description of the region the data is located in (e.g., "resource
+    /// directory"), if any
+    pub data_directory: Option<Arc<String>>,
+}
+
+/// Describes how a binary file's content is laid out, so that file offsets can
+/// be mapped back to sections and virtual addresses.
+#[derive(Debug, Default)]
+pub struct BinaryFileLayout {
+    /// Architecture slices contained in the file (universal binaries only)
+    slices: Vec<BinaryRegion>,
+    /// Members contained in the file (archives only)
+    members: Vec<BinaryRegion>,
+    /// Sections sorted by file offset
+    sections: Vec<BinarySection>,
+    /// Special regions of the file, which may overlap with sections
+    regions: Vec<BinaryRegion>,
+}
+
+impl BinaryFileLayout {
+    fn new(mut sections: Vec<BinarySection>, regions: Vec<BinaryRegion>) -> Self {
+        sections.sort_by_key(|section| section.file_offset);
+
+        Self {
+            slices: vec![],
+            members: vec![],
+            sections,
+            regions,
+        }
+    }
+
+    /// Moves every element of the layout by `offset` bytes. This is used for
+    /// layouts of files embedded in other files (e.g., archive members).
+    fn shifted(mut self, offset: u64) -> Self {
+        for section in self.sections.iter_mut() {
+            section.file_offset += offset;
+        }
+        for region in self
+            .slices
+            .iter_mut()
+            .chain(self.members.iter_mut())
+            .chain(self.regions.iter_mut())
+        {
+            region.file_offset += offset;
+        }
+
+        self
+    }
+
+    /// Merges the elements of `other` into this layout.
+    fn merge(&mut self, other: BinaryFileLayout) {
+        self.slices.extend(other.slices);
+        self.members.extend(other.members);
+        self.sections.extend(other.sections);
+        self.sections.sort_by_key(|section| section.file_offset);
+        self.regions.extend(other.regions);
+    }
+
+    /// Parses the headers of the given binary file. Unsupported binary formats
+    /// result in an empty layout, for which no offset can be located.
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        match FileKind::parse(data) {
+            Ok(FileKind::Elf32) => elf::parse_elf_layout::<object::elf::FileHeader32<_>>(data),
+            Ok(FileKind::Elf64) => elf::parse_elf_layout::<object::elf::FileHeader64<_>>(data),
+            Ok(FileKind::Pe32) => pe::parse_pe_layout::<object::pe::ImageNtHeaders32>(data),
+            Ok(FileKind::Pe64) => pe::parse_pe_layout::<object::pe::ImageNtHeaders64>(data),
+            Ok(FileKind::Coff) => pe::parse_coff_layout(data),
+            Ok(FileKind::MachO32) => {
+                macho::parse_macho_layout::<object::macho::MachHeader32<_>>(data)
+            }
+            Ok(FileKind::MachO64) => {
+                macho::parse_macho_layout::<object::macho::MachHeader64<_>>(data)
+            }
+            Ok(FileKind::MachOFat32) => {
+                macho::parse_fat_layout(data, FatHeader::parse_arch32(data)?)
+            }
+            Ok(FileKind::MachOFat64) => {
+                macho::parse_fat_layout(data, FatHeader::parse_arch64(data)?)
+            }
+            Ok(FileKind::Archive) => archive::parse_archive_layout(data),
+            _ => {
+                log::debug!("Unsupported binary format, section information won't be available");
+                Ok(Self::default())
+            }
+        }
+    }
+
+    /// Returns the ranges of the file that should be scanned independently
+    /// (i.e., one range per member for archives, plus the ranges between
+    /// members, one range per architecture slice for universal binaries, and a
+    /// single range covering the whole file otherwise), minus the sections
+    /// that `section_filter` excludes.
+    pub fn scan_ranges(&self, file_size: u64, section_filter: &SectionFilter) -> Vec<Range<u64>> {
+        let base_ranges: Vec<Range<u64>> = if !self.members.is_empty() {
+            self.archive_ranges(file_size)
+        } else if !self.slices.is_empty() {
+            self.slices.iter().map(BinaryRegion::file_range).collect()
+        } else {
+            std::iter::once(0..file_size).collect()
+        };
+
+        if section_filter.is_empty() {
+            return base_ranges;
+        }
+        if self.sections.is_empty() {
+            log::warn!("No section information available, section filters are ignored");
+            return base_ranges;
+        }
+
+        base_ranges
+            .into_iter()
+            .flat_map(|base_range| self.filter_range(base_range, section_filter))
+            .collect()
+    }
+
+    /// Returns the range of each archive member, as well as the ranges between
+    /// members. The latter contain the archive's symbol table and long names
+    /// table, which list exported symbols and the names of the members.
+    fn archive_ranges(&self, file_size: u64) -> Vec<Range<u64>> {
+        let mut ranges = vec![];
+        let mut start = 0;
+        for member_range in self.members.iter().map(BinaryRegion::file_range) {
+            if start < member_range.start {
+                ranges.push(start..member_range.start);
+            }
+            start = start.max(member_range.end);
+            ranges.push(member_range);
+        }
+        if start < file_size {
+            ranges.push(start..file_size);
+        }
+
+        ranges
+    }
+
+    /// Splits `base_range` into the sub-ranges that should be scanned according
+    /// to `section_filter`.
+    fn filter_range(
+        &self,
+        base_range: Range<u64>,
+        section_filter: &SectionFilter,
+    ) -> Vec<Range<u64>> {
+        let sections_in_range = self.sections.iter().filter(|section| {
+            base_range.start <= section.file_offset
+                && section.file_offset + section.file_size <= base_range.end
+        });
+
+        let mut ranges: Vec<Range<u64>> = vec![];
+        if section_filter.is_unsectioned_data_scanned() {
+            // Start from the whole range and remove excluded sections
+            let mut start = base_range.start;
+            for section in sections_in_range {
+                if !section_filter.is_section_scanned(&section.name) {
+                    if start < section.file_offset {
+                        ranges.push(start..section.file_offset);
+                    }
+                    start = start.max(section.file_offset + section.file_size);
+                }
+            }
+            if start < base_range.end {
+                ranges.push(start..base_range.end);
+            }
+        } else {
+            // Only keep selected sections, merging contiguous ones
+            for section in sections_in_range {
+                if section_filter.is_section_scanned(&section.name) {
+                    let section_end = section.file_offset + section.file_size;
+                    match ranges.last_mut() {
+                        Some(last_range) if section.file_offset <= last_range.end => {
+                            last_range.end = last_range.end.max(section_end);
+                        }
+                        _ => ranges.push(section.file_offset..section_end),
+                    }
+                }
+            }
+        }
+
+        ranges
+    }
+
+    /// Returns the location of the data at the given file offset.
+    pub fn locate(&self, file_offset: u64) -> BinaryFileLocation {
+        let architecture = self
+            .slices
+            .iter()
+            .find(|slice| slice.contains(file_offset))
+            .map(|slice| slice.name.clone());
+        let archive_member = self
+            .members
+            .iter()
+            .find(|member| member.contains(file_offset))
+            .map(|member| member.name.clone());
+
+        match self.locate_section(file_offset) {
+            Some(section) => {
+                let offset_in_section = file_offset - section.file_offset;
+                BinaryFileLocation {
+                    architecture,
+                    archive_member,
+                    section: Some(section.name.clone()),
+                    segment: section.segment.clone(),
+                    virtual_address: section
+                        .virtual_address
+                        .map(|address| address + offset_in_section),
+                    relative_virtual_address: section
+                        .relative_virtual_address
+                        .map(|address| address + offset_in_section),
+                    data_directory: self.locate_region(file_offset),
+                }
+            }
+            None => BinaryFileLocation {
+                architecture,
+                archive_member,
+                data_directory: self.locate_region(file_offset),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Returns the section containing the given file offset, if any.
+    fn locate_section(&self, file_offset: u64) -> Option<&BinarySection> {
+        // Find the last section starting before or at `file_offset`
+        let index = self
+            .sections
+            .partition_point(|section| section.file_offset <= file_offset);
+        let section = self.sections.get(index.checked_sub(1)?)?;
+        if file_offset - section.file_offset < section.file_size {
+            Some(section)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the name of the innermost region containing the given file
+    /// offset, if any.
+    fn locate_region(&self, file_offset: u64) -> Option<Arc<String>> {
+        self.regions
+            .iter()
+            .filter(|region| region.contains(file_offset))
+            .min_by_key(|region| region.file_size)
+            .map(|region| region.name.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use super::*;
+
+    const ELF_FILE_PATH: &str = "tests/data/main/file_list_proj/a.out";
+    const PE_FILE_PATH: &str = "tests/data/main/file_list_proj/a.exe";
+    const PE_DIRECTORIES_FILE_PATH: &str = "tests/data/binary_format/pdb_and_resources.exe";
+    const PE_LONG_SECTION_NAMES_FILE_PATH: &str = "tests/data/binary_format/long_section_names.exe";
+    const UNIVERSAL_FILE_PATH: &str = "tests/data/binary_format/universal.macho";
+    const ARCHIVE_FILE_PATH: &str = "tests/data/binary_format/libmixed.a";
+
+    #[test]
+    fn parse_unsupported_format() {
+        let layout = BinaryFileLayout::parse(b"not a binary file").expect("parse failed");
+        assert_eq!(layout.locate(0), BinaryFileLocation::default());
+        let scan_ranges = layout.scan_ranges(17, &SectionFilter::default());
+        assert_eq!(scan_ranges.len(), 1);
+        assert_eq!(scan_ranges[0], 0..17);
+    }
+
+    #[test]
+    fn locate_in_elf_loaded_section() {
+        let file_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(ELF_FILE_PATH);
+        let data = fs::read(file_path).expect("Failed to read ELF file");
+        let layout = BinaryFileLayout::parse(&data).expect("parse failed");
+
+        // "included_string_literal"
+        let location = layout.locate(0x2020);
+        assert_eq!(
+            location.section.as_deref().map(String::as_str),
+            Some(".rodata")
+        );
+        assert_eq!(
+            location.segment.as_deref().map(String::as_str),
+            Some("LOAD[4]")
+        );
+        assert_eq!(location.virtual_address, Some(0x2020));
+    }
+
+    #[test]
+    fn locate_in_elf_non_loaded_section() {
+        let file_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(ELF_FILE_PATH);
+        let data = fs::read(file_path).expect("Failed to read ELF file");
+        let layout = BinaryFileLayout::parse(&data).expect("parse failed");
+
+        // "included_string_literal", in the symbol table's strings
+        let location = layout.locate(0x39e5);
+        assert_eq!(
+            location.section.as_deref().map(String::as_str),
+            Some(".strtab")
+        );
+        assert_eq!(location.segment, None);
+        assert_eq!(location.virtual_address, None);
+    }
+
+    #[test]
+    fn locate_in_elf_headers() {
+        let file_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(ELF_FILE_PATH);
+        let data = fs::read(file_path).expect("Failed to read ELF file");
+        let layout = BinaryFileLayout::parse(&data).expect("parse failed");
+
+        // ELF header, not part of any section
+        assert_eq!(layout.locate(0), BinaryFileLocation::default());
+    }
+
+    #[test]
+    fn locate_in_pe_section() {
+        let file_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(PE_FILE_PATH);
+        let data = fs::read(file_path).expect("Failed to read PE file");
+        let layout = BinaryFileLayout::parse(&data).expect("parse failed");
+
+        // "included_string_literal"
+        let location = layout.locate(0x15f20);
+        assert_eq!(
+            location.section.as_deref().map(String::as_str),
+            Some(".rdata")
+        );
+        assert_eq!(location.segment, None);
+        assert_eq!(location.relative_virtual_address, Some(0x17320));
+        assert_eq!(location.virtual_address, Some(0x1_4001_7320));
+        assert_eq!(location.data_directory, None);
+    }
+
+    #[test]
+    fn locate_in_pe_debug_directory() {
+        let file_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(PE_FILE_PATH);
+        let data = fs::read(file_path).expect("Failed to read PE file");
+        let layout = BinaryFileLayout::parse(&data).expect("parse failed");
+
+        // Debug directory's entries
+        let location = layout.locate(0x1e3b0);
+        assert_eq!(
+            location.section.as_deref().map(String::as_str),
+            Some(".rdata")
+        );
+        assert_eq!(
+            location.data_directory.as_deref().map(String::as_str),
+            Some("debug directory")
+        );
+    }
+
+    #[test]
+    fn locate_in_pe_pdb_path_and_resources() {
+        let file_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(PE_DIRECTORIES_FILE_PATH);
+        let data = fs::read(file_path).expect("Failed to read PE file");
+        let layout = BinaryFileLayout::parse(&data).expect("parse failed");
+
+        // CodeView record's signature
+        let location = layout.locate(0x220);
+        assert_eq!(
+            location.data_directory.as_deref().map(String::as_str),
+            Some("debug directory")
+        );
+        // PDB path
+        let location = layout.locate(0x238);
+        assert_eq!(
+            location.section.as_deref().map(String::as_str),
+            Some(".rdata")
+        );
+        assert_eq!(location.relative_virtual_address, Some(0x1038));
+        assert_eq!(
+            location.data_directory.as_deref().map(String::as_str),
+            Some("debug directory (PDB path)")
+        );
+        // Regular data
+        let location = layout.locate(0x280);
+        assert_eq!(
+            location.section.as_deref().map(String::as_str),
+            Some(".rdata")
+        );
+        assert_eq!(location.data_directory, None);
+        // Resources
+        let location = layout.locate(0x410);
+        assert_eq!(
+            location.section.as_deref().map(String::as_str),
+            Some(".rsrc")
+        );
+        assert_eq!(location.virtual_address, Some(0x1_4000_2010));
+        assert_eq!(
+            location.data_directory.as_deref().map(String::as_str),
+            Some("resource directory")
+        );
+    }
+
+    #[test]
+    fn locate_in_pe_long_section_names() {
+        let file_path =
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(PE_LONG_SECTION_NAMES_FILE_PATH);
+        let data = fs::read(file_path).expect("Failed to read PE file");
+        let layout = BinaryFileLayout::parse(&data).expect("parse failed");
+
+        // Sections named "/4" and "/16" in the section table (as produced by
+        // MinGW), resolved through the COFF string table
+        assert_eq!(
+            layout.locate(0xa00).section.as_deref().map(String::as_str),
+            Some(".debug_info")
+        );
+        assert_eq!(
+            layout.locate(0xc00).section.as_deref().map(String::as_str),
+            Some(".debug_abbrev")
+        );
+        // Short names are stored in the section table
+        assert_eq!(
+            layout.locate(0x600).section.as_deref().map(String::as_str),
+            Some(".rdata")
+        );
+
+        // Sections with long names can be excluded
+        let section_filter =
+            SectionFilter::new(&[], &[".debug_*".to_string()]).expect("SectionFilter::new failed");
+        assert_eq!(
+            layout.scan_ranges(data.len() as u64, &section_filter),
+            vec![
+                0..0xa00,
+                0xa19..0xc00,
+                0xc1b..0xe00,
+                0xe1d..data.len() as u64
+            ]
+        );
+    }
+
+    #[test]
+    fn scan_ranges_universal_binary() {
+        let file_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(UNIVERSAL_FILE_PATH);
+        let data = fs::read(file_path).expect("Failed to read universal binary");
+        let layout = BinaryFileLayout::parse(&data).expect("parse failed");
+
+        // One range per slice
+        assert_eq!(
+            layout.scan_ranges(data.len() as u64, &SectionFilter::default()),
+            vec![0x1000..0x1300, 0x2000..0x2300]
+        );
+    }
+
+    #[test]
+    fn locate_in_universal_binary() {
+        let file_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(UNIVERSAL_FILE_PATH);
+        let data = fs::read(file_path).expect("Failed to read universal binary");
+        let layout = BinaryFileLayout::parse(&data).expect("parse failed");
+
+        // "shared_string" in the x86_64 slice
+        let location = layout.locate(0x1200);
+        assert_eq!(
+            location.architecture.as_deref().map(String::as_str),
+            Some("x86_64")
+        );
+        assert_eq!(
+            location.section.as_deref().map(String::as_str),
+            Some("__TEXT,__cstring")
+        );
+        assert_eq!(
+            location.segment.as_deref().map(String::as_str),
+            Some("__TEXT")
+        );
+        assert_eq!(location.virtual_address, Some(0x1_0000_0200));
+        // "arm64_only_string" in the arm64 slice
+        let location = layout.locate(0x220e);
+        assert_eq!(
+            location.architecture.as_deref().map(String::as_str),
+            Some("arm64")
+        );
+        assert_eq!(
+            location.section.as_deref().map(String::as_str),
+            Some("__TEXT,__cstring")
+        );
+        assert_eq!(location.virtual_address, Some(0x1_0000_020e));
+        // Constant data in the arm64 slice
+        let location = layout.locate(0x2245);
+        assert_eq!(
+            location.section.as_deref().map(String::as_str),
+            Some("__DATA,__const")
+        );
+        assert_eq!(
+            location.segment.as_deref().map(String::as_str),
+            Some("__DATA")
+        );
+        // Universal binary's header
+        assert_eq!(layout.locate(0x8), BinaryFileLocation::default());
+    }
+
+    #[test]
+    fn parse_universal_binary_with_overflowing_slice_range() {
+        // 64-bit universal binary header, with a single slice whose end
+        // offset overflows
+        let mut data = vec![];
+        data.extend(0xcafe_babf_u32.to_be_bytes()); // magic
+        data.extend(1_u32.to_be_bytes()); // nfat_arch
+        data.extend(object::macho::CPU_TYPE_X86_64.to_be_bytes()); // cputype
+        data.extend(3_u32.to_be_bytes()); // cpusubtype
+        data.extend((u64::MAX - 0x10).to_be_bytes()); // offset
+        data.extend(0x100_u64.to_be_bytes()); // size
+        data.extend(0_u32.to_be_bytes()); // align
+        data.extend(0_u32.to_be_bytes()); // reserved
+        data.resize(0x100, 0);
+
+        assert!(BinaryFileLayout::parse(&data).is_err());
+    }
+
+    #[test]
+    fn locate_in_macho_object_file() {
+        // 64-bit Mach-O object file, with a single "__TEXT,__cstring" section
+        // containing "abc"
+        let mut data = vec![];
+        data.extend(0xfeed_facf_u32.to_le_bytes()); // magic
+        data.extend(object::macho::CPU_TYPE_X86_64.to_le_bytes()); // cputype
+        data.extend(3_u32.to_le_bytes()); // cpusubtype
+        data.extend(object::macho::MH_OBJECT.to_le_bytes()); // filetype
+        data.extend(1_u32.to_le_bytes()); // ncmds
+        data.extend(152_u32.to_le_bytes()); // sizeofcmds
+        data.extend([0; 8]); // flags, reserved
+        data.extend(object::macho::LC_SEGMENT_64.to_le_bytes()); // cmd
+        data.extend(152_u32.to_le_bytes()); // cmdsize
+        data.extend([0; 16]); // segname
+        data.extend(0_u64.to_le_bytes()); // vmaddr
+        data.extend(4_u64.to_le_bytes()); // vmsize
+        data.extend(184_u64.to_le_bytes()); // fileoff
+        data.extend(4_u64.to_le_bytes()); // filesize
+        data.extend(7_u32.to_le_bytes()); // maxprot
+        data.extend(7_u32.to_le_bytes()); // initprot
+        data.extend(1_u32.to_le_bytes()); // nsects
+        data.extend(0_u32.to_le_bytes()); // flags
+        data.extend(*b"__cstring\0\0\0\0\0\0\0"); // sectname
+        data.extend(*b"__TEXT\0\0\0\0\0\0\0\0\0\0"); // segname
+        data.extend(0x10_u64.to_le_bytes()); // addr
+        data.extend(4_u64.to_le_bytes()); // size
+        data.extend(184_u32.to_le_bytes()); // offset
+        data.extend([0; 12]); // align, reloff, nreloc
+        data.extend(object::macho::S_CSTRING_LITERALS.to_le_bytes()); // flags
+        data.extend([0; 12]); // reserved1, reserved2, reserved3
+        data.extend(*b"abc\0");
+        let layout = BinaryFileLayout::parse(&data).expect("parse failed");
+
+        // Sections of object files have no virtual address
+        let location = layout.locate(184);
+        assert!(location.section.is_some());
+        assert_eq!(location.virtual_address, None);
+    }
+
+    #[test]
+    fn scan_ranges_only_sections() {
+        let file_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(ELF_FILE_PATH);
+        let data = fs::read(file_path).expect("Failed to read ELF file");
+        let layout = BinaryFileLayout::parse(&data).expect("parse failed");
+        let section_filter =
+            SectionFilter::new(&[".rodata".to_string(), ".data*".to_string()], &[])
+                .expect("SectionFilter::new failed");
+
+        assert_eq!(
+            layout.scan_ranges(data.len() as u64, &section_filter),
+            vec![0x2000..0x2268, 0x2cf8..0x2dc0, 0x3030..0x3050]
+        );
+    }
+
+    #[test]
+    fn scan_ranges_excluded_sections() {
+        let file_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(ELF_FILE_PATH);
+        let data = fs::read(file_path).expect("Failed to read ELF file");
+        let layout = BinaryFileLayout::parse(&data).expect("parse failed");
+        let section_filter =
+            SectionFilter::new(&[], &[".*tab".to_string()]).expect("SectionFilter::new failed");
+
+        // ".symtab", ".strtab" and ".shstrtab" are contiguous
+        assert_eq!(
+            layout.scan_ranges(data.len() as u64, &section_filter),
+            vec![0..0x3078, 0x4887..data.len() as u64]
+        );
+    }
+
+    #[test]
+    fn scan_ranges_universal_binary_only_sections() {
+        let file_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(UNIVERSAL_FILE_PATH);
+        let data = fs::read(file_path).expect("Failed to read universal binary");
+        let layout = BinaryFileLayout::parse(&data).expect("parse failed");
+        let section_filter =
+            SectionFilter::new(&["__DATA,*".to_string()], &[]).expect("SectionFilter::new failed");
+
+        // One range per slice
+        assert_eq!(
+            layout.scan_ranges(data.len() as u64, &section_filter),
+            vec![0x1240..0x1260, 0x2240..0x2260]
+        );
+    }
+
+    #[test]
+    fn scan_ranges_archive() {
+        let file_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(ARCHIVE_FILE_PATH);
+        let data = fs::read(file_path).expect("Failed to read archive");
+        let layout = BinaryFileLayout::parse(&data).expect("parse failed");
+
+        // One range per member, and one range per gap between members (e.g.,
+        // archive headers)
+        assert_eq!(
+            layout.scan_ranges(data.len() as u64, &SectionFilter::default()),
+            vec![0..0x44, 0x44..0x394, 0x394..0x3d0, 0x3d0..0x430]
+        );
+    }
+
+    #[test]
+    fn locate_in_archive() {
+        let file_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(ARCHIVE_FILE_PATH);
+        let data = fs::read(file_path).expect("Failed to read archive");
+        let layout = BinaryFileLayout::parse(&data).expect("parse failed");
+
+        // "foo_secret_string" in an ELF object file
+        let location = layout.locate(0x8c);
+        assert_eq!(
+            location.archive_member.as_deref().map(String::as_str),
+            Some("foo.o")
+        );
+        assert_eq!(
+            location.section.as_deref().map(String::as_str),
+            Some(".rodata.str1.1")
+        );
+        assert_eq!(location.virtual_address, None);
+        // "bar_secret_string" in a COFF object file, with a long section name
+        let location = layout.locate(0x40c);
+        assert_eq!(
+            location.archive_member.as_deref().map(String::as_str),
+            Some("bar.obj")
+        );
+        assert_eq!(
+            location.section.as_deref().map(String::as_str),
+            Some(".rdata$secret")
+        );
+        assert_eq!(location.virtual_address, None);
+        // Archive's header
+        assert_eq!(layout.locate(0x0), BinaryFileLocation::default());
+    }
+}
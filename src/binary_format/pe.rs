@@ -0,0 +1,186 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use object::{
+    pe::{
+        ImageDebugDirectory, ImageFileHeader, IMAGE_DEBUG_TYPE_CODEVIEW,
+        IMAGE_DIRECTORY_ENTRY_DEBUG, IMAGE_DIRECTORY_ENTRY_RESOURCE,
+    },
+    read::{
+        coff::SectionTable,
+        pe::{ImageNtHeaders, ImageOptionalHeader, PeFile},
+    },
+    LittleEndian as LE, ReadRef,
+};
+
+use super::{BinaryFileLayout, BinaryRegion, BinarySection};
+
+const RESOURCE_DIRECTORY_NAME: &str = "resource directory";
+const DEBUG_DIRECTORY_NAME: &str = "debug directory";
+const PDB_PATH_NAME: &str = "debug directory (PDB path)";
+/// Signature of CodeView records that reference a PDB file (i.e., PDB 7.0)
+const CODEVIEW_PDB70_SIGNATURE: &[u8] = b"RSDS";
+/// Offset of the PDB path in PDB 7.0 CodeView records (after the signature,
+/// GUID and age fields)
+const CODEVIEW_PDB70_PATH_OFFSET: u64 = 24;
+
+/// Extracts sections, as well as the location of the resource and debug
+/// directories from a PE file's headers.
+pub fn parse_pe_layout<Pe: ImageNtHeaders>(data: &[u8]) -> Result<BinaryFileLayout> {
+    let pe_file = PeFile::<Pe>::parse(data)?;
+    let image_base = pe_file.nt_headers().optional_header().image_base();
+    // Long section names (e.g., the ".debug_*" sections of MinGW binaries) are
+    // stored in the COFF string table
+    let strings = pe_file
+        .nt_headers()
+        .file_header()
+        .symbols(data)
+        .ok()
+        .map(|symbols| symbols.strings());
+
+    let sections = pe_file
+        .section_table()
+        .iter()
+        .filter_map(|section| {
+            let (file_offset, raw_size) = section.coff_file_range()?;
+            // Raw data is padded to the file alignment, ignore the padding if
+            // the virtual size is known
+            let virtual_size = section.virtual_size.get(LE);
+            let file_size = if virtual_size == 0 {
+                raw_size
+            } else {
+                raw_size.min(virtual_size)
+            };
+            if file_size == 0 {
+                return None;
+            }
+            let name = strings
+                .and_then(|strings| section.name(strings).ok())
+                .unwrap_or_else(|| section.raw_name());
+            let relative_virtual_address = u64::from(section.virtual_address.get(LE));
+
+            Some(BinarySection {
+                name: Arc::new(String::from_utf8_lossy(name).into_owned()),
+                segment: None,
+                file_offset: file_offset.into(),
+                file_size: file_size.into(),
+                virtual_address: Some(image_base + relative_virtual_address),
+                relative_virtual_address: Some(relative_virtual_address),
+            })
+        })
+        .collect();
+
+    let mut regions = vec![];
+    let section_table = pe_file.section_table();
+    let data_directories = pe_file.data_directories();
+    // Resource directory
+    if let Some(resource_directory) = data_directories.get(IMAGE_DIRECTORY_ENTRY_RESOURCE) {
+        if let Ok((file_offset, file_size)) = resource_directory.file_range(&section_table) {
+            regions.push(BinaryRegion {
+                name: Arc::new(RESOURCE_DIRECTORY_NAME.to_string()),
+                file_offset: file_offset.into(),
+                file_size: file_size.into(),
+            });
+        }
+    }
+    // Debug directory
+    if let Some(debug_directory) = data_directories.get(IMAGE_DIRECTORY_ENTRY_DEBUG) {
+        if let Ok((file_offset, file_size)) = debug_directory.file_range(&section_table) {
+            regions.push(BinaryRegion {
+                name: Arc::new(DEBUG_DIRECTORY_NAME.to_string()),
+                file_offset: file_offset.into(),
+                file_size: file_size.into(),
+            });
+            regions.extend(parse_debug_directory_entries(
+                data,
+                file_offset.into(),
+                file_size as usize,
+            ));
+        }
+    }
+
+    Ok(BinaryFileLayout::new(sections, regions))
+}
+
+/// Extracts sections from a COFF object file's headers (e.g., members of MSVC
+/// `.lib` archives). Object files aren't loaded as is, so sections don't have
+/// virtual addresses.
+pub fn parse_coff_layout(data: &[u8]) -> Result<BinaryFileLayout> {
+    let mut offset = 0;
+    let header = ImageFileHeader::parse(data, &mut offset)?;
+    let section_table: SectionTable = header.sections(data, offset)?;
+    let strings = header.symbols(data)?.strings();
+
+    let sections = section_table
+        .iter()
+        .filter_map(|section| {
+            let (file_offset, file_size) = section.coff_file_range()?;
+            if file_size == 0 {
+                return None;
+            }
+            // Long section names are stored in the string table
+            let name = section.name(strings).ok()?;
+
+            Some(BinarySection {
+                name: Arc::new(String::from_utf8_lossy(name).into_owned()),
+                segment: None,
+                file_offset: file_offset.into(),
+                file_size: file_size.into(),
+                virtual_address: None,
+                relative_virtual_address: None,
+            })
+        })
+        .collect();
+
+    Ok(BinaryFileLayout::new(sections, vec![]))
+}
+
+/// Returns the regions containing the data referenced by each entry of the
+/// debug directory. The PDB path from CodeView records gets its own region.
+fn parse_debug_directory_entries(
+    data: &[u8],
+    directory_offset: u64,
+    directory_size: usize,
+) -> Vec<BinaryRegion> {
+    let entry_count = directory_size / std::mem::size_of::<ImageDebugDirectory>();
+    let entries = match data.read_slice_at::<ImageDebugDirectory>(directory_offset, entry_count) {
+        Ok(entries) => entries,
+        Err(_) => {
+            log::warn!("Invalid debug directory, ignoring it");
+            return vec![];
+        }
+    };
+
+    let mut regions = vec![];
+    for entry in entries {
+        let file_offset = u64::from(entry.pointer_to_raw_data.get(LE));
+        let file_size = u64::from(entry.size_of_data.get(LE));
+        if file_offset == 0 || file_size == 0 {
+            // Data isn't mapped in the file
+            continue;
+        }
+        regions.push(BinaryRegion {
+            name: Arc::new(DEBUG_DIRECTORY_NAME.to_string()),
+            file_offset,
+            file_size,
+        });
+
+        // Locate the PDB path in CodeView records
+        if entry.typ.get(LE) == IMAGE_DEBUG_TYPE_CODEVIEW
+            && data.read_bytes_at(file_offset, CODEVIEW_PDB70_SIGNATURE.len() as u64)
+                == Ok(CODEVIEW_PDB70_SIGNATURE)
+        {
+            let path_offset = file_offset + CODEVIEW_PDB70_PATH_OFFSET;
+            if let Ok(pdb_path) = data.read_bytes_at_until(path_offset..file_offset + file_size, 0)
+            {
+                regions.push(BinaryRegion {
+                    name: Arc::new(PDB_PATH_NAME.to_string()),
+                    file_offset: path_offset,
+                    file_size: pdb_path.len() as u64,
+                });
+            }
+        }
+    }
+
+    regions
+}
@@ -0,0 +1,106 @@
+use anyhow::{Context, Result};
+use glob::Pattern;
+
+/// Struct describing which sections of binary files should be scanned.
+#[derive(Debug, Default)]
+pub struct SectionFilter {
+    /// If not empty, only sections matching one of these are scanned
+    only_sections: Vec<Pattern>,
+    /// Sections matching one of these are never scanned
+    excluded_sections: Vec<Pattern>,
+}
+
+impl SectionFilter {
+    /// Compiles the given glob expressions into a `SectionFilter`.
+    pub fn new(only_sections: &[String], excluded_sections: &[String]) -> Result<Self> {
+        Ok(Self {
+            only_sections: compile_patterns(only_sections)?,
+            excluded_sections: compile_patterns(excluded_sections)?,
+        })
+    }
+
+    /// Indicates if the filter doesn't exclude anything
+    pub fn is_empty(&self) -> bool {
+        self.only_sections.is_empty() && self.excluded_sections.is_empty()
+    }
+
+    /// Indicates if data located outside of any section should be scanned
+    pub fn is_unsectioned_data_scanned(&self) -> bool {
+        self.only_sections.is_empty()
+    }
+
+    /// Indicates if the section with the given name should be scanned
+    pub fn is_section_scanned(&self, section_name: &str) -> bool {
+        (self.only_sections.is_empty()
+            || self
+                .only_sections
+                .iter()
+                .any(|pattern| pattern.matches(section_name)))
+            && !self
+                .excluded_sections
+                .iter()
+                .any(|pattern| pattern.matches(section_name))
+    }
+}
+
+fn compile_patterns(glob_expressions: &[String]) -> Result<Vec<Pattern>> {
+    glob_expressions
+        .iter()
+        .map(|expression| {
+            Pattern::new(expression)
+                .with_context(|| format!("'{}' is not a valid glob expression", expression))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_filter() {
+        let filter = SectionFilter::new(&[], &[]).expect("SectionFilter::new failed");
+
+        assert!(filter.is_empty());
+        assert!(filter.is_unsectioned_data_scanned());
+        assert!(filter.is_section_scanned(".debug_str"));
+    }
+
+    #[test]
+    fn only_sections() {
+        let filter = SectionFilter::new(&[".rodata".to_string(), ".data*".to_string()], &[])
+            .expect("SectionFilter::new failed");
+
+        assert!(!filter.is_empty());
+        assert!(!filter.is_unsectioned_data_scanned());
+        assert!(filter.is_section_scanned(".rodata"));
+        assert!(filter.is_section_scanned(".data.rel.ro"));
+        assert!(!filter.is_section_scanned(".comment"));
+    }
+
+    #[test]
+    fn excluded_sections() {
+        let filter =
+            SectionFilter::new(&[], &[".debug_*".to_string()]).expect("SectionFilter::new failed");
+
+        assert!(!filter.is_empty());
+        assert!(filter.is_unsectioned_data_scanned());
+        assert!(filter.is_section_scanned(".rodata"));
+        assert!(!filter.is_section_scanned(".debug_str"));
+    }
+
+    #[test]
+    fn only_and_excluded_sections() {
+        let filter = SectionFilter::new(&["__TEXT,*".to_string()], &["__TEXT,__text".to_string()])
+            .expect("SectionFilter::new failed");
+
+        assert!(filter.is_section_scanned("__TEXT,__cstring"));
+        assert!(!filter.is_section_scanned("__TEXT,__text"));
+        assert!(!filter.is_section_scanned("__DATA,__const"));
+    }
+
+    #[test]
+    fn invalid_pattern() {
+        assert!(SectionFilter::new(&["[".to_string()], &[]).is_err());
+    }
+}
@@ -1,16 +1,101 @@
 use std::path::PathBuf;
 
-use structopt::StructOpt;
+use structopt::{clap::AppSettings, StructOpt};
+
+use cpplumber::{
+    reporting::{
+        LeakGrouping, ReportFormat, ReportOptions, LEAK_GROUPING_NAMES, REPORT_FORMAT_NAMES,
+    },
+    MatchingEngine, MATCHING_ENGINE_NAMES,
+};
 
 const PKG_NAME: &str = env!("CARGO_PKG_NAME");
 
+/// When no subcommand is used, artifacts are extracted from source files and
+/// looked for in binary files in one go.
 #[derive(Debug, StructOpt)]
-#[structopt(name = PKG_NAME, about = "An information leak detector for C and C++ code bases")]
+#[structopt(
+    name = PKG_NAME,
+    about = "An information leak detector for C and C++ code bases",
+    setting = AppSettings::SubcommandsNegateReqs
+)]
 pub struct CpplumberOptions {
-    /// Path to the output binary to scan for leaked information.
-    #[structopt(parse(from_os_str), short, long = "bin")]
-    pub binary_file_path: PathBuf,
+    #[structopt(subcommand)]
+    pub command: Option<Command>,
+
+    #[structopt(flatten)]
+    pub common: CommonOptions,
+
+    #[structopt(flatten)]
+    pub extraction: ExtractionOptions,
+
+    #[structopt(flatten)]
+    pub matching: MatchingOptions,
+}
+
+#[derive(Debug, StructOpt)]
+pub enum Command {
+    /// Extract artifacts from source files and save them into an artifact
+    /// file, to be used with the `match` subcommand.
+    Extract {
+        /// Path of the artifact file to generate.
+        #[structopt(parse(from_os_str), short, long = "output")]
+        output_file_path: PathBuf,
+
+        #[structopt(flatten)]
+        common: CommonOptions,
+
+        #[structopt(flatten)]
+        extraction: ExtractionOptions,
+    },
+    /// Look for leaks of the artifacts contained in an artifact file generated
+    /// by the `extract` subcommand. Doesn't require libclang.
+    Match {
+        /// Path of the artifact file to use.
+        #[structopt(parse(from_os_str), short, long = "artifacts")]
+        artifact_file_path: PathBuf,
+
+        #[structopt(flatten)]
+        common: CommonOptions,
+
+        #[structopt(flatten)]
+        matching: MatchingOptions,
+    },
+    /// Compare two JSON reports and list leaks that were added, removed or
+    /// moved (i.e., same data found at a different location).
+    Diff {
+        /// Path of the JSON report of the old build.
+        #[structopt(parse(from_os_str))]
+        old_report_path: PathBuf,
 
+        /// Path of the JSON report of the new build.
+        #[structopt(parse(from_os_str))]
+        new_report_path: PathBuf,
+
+        /// Generate output as JSON.
+        #[structopt(short, long = "json")]
+        json_output: bool,
+    },
+}
+
+/// Options used for both extraction and matching
+#[derive(Debug, StructOpt)]
+pub struct CommonOptions {
+    /// Path to a file containing rules to prevent certain errors from being
+    /// generated.
+    #[structopt(parse(from_os_str), short, long)]
+    pub suppressions_list: Option<PathBuf>,
+
+    /// Number of threads used to parse source files and scan binary files.
+    /// Defaults to the number of logical CPUs.
+    #[structopt(long)]
+    pub jobs: Option<usize>,
+}
+
+/// Options related to the extraction of artifacts from source files
+#[derive(Debug, StructOpt)]
+#[cfg_attr(not(feature = "extraction"), allow(dead_code))]
+pub struct ExtractionOptions {
     /// Additional include directories.
     /// Only used when project files aren't used.
     #[structopt(short = "I")]
@@ -25,15 +110,6 @@ pub struct CpplumberOptions {
     #[structopt(parse(from_os_str), short, long = "project")]
     pub project_file_path: Option<PathBuf>,
 
-    /// Path to a file containing rules to prevent certain errors from being
-    /// generated.
-    #[structopt(parse(from_os_str), short, long)]
-    pub suppressions_list: Option<PathBuf>,
-
-    /// Report leaked values only once, even when found in multiple locations.
-    #[structopt(long)]
-    pub ignore_multiple_locations: bool,
-
     /// Report leaks for data declared in system headers
     #[structopt(long)]
     pub report_system_headers: bool,
@@ -52,10 +128,286 @@ pub struct CpplumberOptions {
     #[structopt(long)]
     pub ignore_struct_names: bool,
 
-    /// Generate output as JSON.
-    #[structopt(short, long = "json")]
-    pub json_output: bool,
+    /// Ignore leaks of function and method names.
+    #[structopt(long)]
+    pub ignore_function_names: bool,
+
+    /// Ignore leaks of enum names.
+    #[structopt(long)]
+    pub ignore_enum_names: bool,
+
+    /// Ignore leaks of enum constant names.
+    #[structopt(long)]
+    pub ignore_enum_constant_names: bool,
+
+    /// Ignore leaks of global and static variable names.
+    #[structopt(long)]
+    pub ignore_variable_names: bool,
+
+    /// Ignore leaks of namespace names.
+    #[structopt(long)]
+    pub ignore_namespace_names: bool,
+
+    /// Directory where artifacts extracted from source files are cached, to
+    /// avoid parsing unchanged files again. Artifacts are not cached by
+    /// default. Warning: Anyone able to write to this directory can hide
+    /// leaks, it must not be shared with untrusted users.
+    #[structopt(parse(from_os_str), long)]
+    pub cache_dir: Option<PathBuf>,
+
+    /// Do not use cached artifacts, extract artifacts from all source files
+    /// again. The cache given with --cache-dir is refreshed with them.
+    #[structopt(long)]
+    pub no_cache: bool,
 
     /// List of source files to scan for (can be glob expressions).
     pub source_path_globs: Vec<String>,
 }
+
+/// Options related to the search of leaks in binary files
+#[derive(Debug, StructOpt)]
+pub struct MatchingOptions {
+    /// Paths to the output binaries to scan for leaked information (can be
+    /// glob expressions). Can be specified multiple times (use
+    /// `--group-by binary` to group the leaks of each binary in text reports).
+    #[structopt(short, long = "bin", required = true, number_of_values = 1)]
+    pub binary_file_path_globs: Vec<String>,
+
+    /// Report leaked values only once, even when found in multiple locations.
+    #[structopt(long)]
+    pub ignore_multiple_locations: bool,
+
+    /// Only scan sections whose name matches one of these glob expressions
+    /// (e.g., ".rodata,.data*"). Data located outside of sections isn't
+    /// scanned when this is used.
+    #[structopt(long, use_delimiter = true)]
+    pub only_sections: Vec<String>,
+
+    /// Do not scan sections whose name matches one of these glob expressions
+    /// (e.g., ".debug_*").
+    #[structopt(long, use_delimiter = true)]
+    pub exclude_sections: Vec<String>,
+
+    /// Algorithm used to look for leaked data in binary files.
+    #[structopt(long = "matcher", default_value = "aho-corasick", possible_values = MATCHING_ENGINE_NAMES)]
+    pub matching_engine: MatchingEngine,
+
+    /// Only report leaks that aren't present in this JSON report (generated
+    /// by a previous run). Leaks are matched by value, type and source file.
+    #[structopt(parse(from_os_str), long = "baseline")]
+    pub baseline_path: Option<PathBuf>,
+
+    /// Format of the generated report (JUnit reports contain one test suite
+    /// per source file, HTML reports are self-contained pages).
+    #[structopt(long = "format", possible_values = REPORT_FORMAT_NAMES)]
+    pub report_format: Option<ReportFormat>,
+
+    /// Generate output as JSON (same as `--format json`).
+    #[structopt(short, long = "json", conflicts_with = "report-format")]
+    pub json_output: bool,
+
+    /// Print the line of source code declaring each leak, with the leaked
+    /// data underlined (text reports only).
+    #[structopt(long)]
+    pub show_source: bool,
+
+    /// Include this many bytes of the binary file before and after each leak
+    /// (as a hex dump in text reports, hex-encoded in JSON reports).
+    #[structopt(long = "context-bytes", default_value = "0")]
+    pub context_size: u64,
+
+    /// Group leaks by source file, binary file, data type or value in text
+    /// reports.
+    #[structopt(long, possible_values = LEAK_GROUPING_NAMES)]
+    pub group_by: Option<LeakGrouping>,
+
+    /// Append a summary of the leaks (counts per data type, source file,
+    /// binary file and section, and most leaked values) to text reports.
+    #[structopt(long = "summary")]
+    pub show_summary: bool,
+}
+
+impl MatchingOptions {
+    /// Returns the format of the report requested by the user
+    pub fn report_format(&self) -> ReportFormat {
+        match self.report_format {
+            Some(format) => format,
+            None if self.json_output => ReportFormat::Json,
+            None => ReportFormat::default(),
+        }
+    }
+
+    /// Returns the report options requested by the user
+    pub fn report_options(&self) -> ReportOptions {
+        ReportOptions {
+            format: self.report_format(),
+            show_source: self.show_source,
+            context_size: self.context_size,
+            group_by: self.group_by,
+            show_summary: self.show_summary,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_options() {
+        let options = CpplumberOptions::from_iter_safe([
+            PKG_NAME, "--bin", "a.out", "--jobs", "4", "--json", "main.cc",
+        ])
+        .expect("from_iter_safe failed");
+
+        assert!(options.command.is_none());
+        assert_eq!(options.matching.binary_file_path_globs, vec!["a.out"]);
+        assert_eq!(options.common.jobs, Some(4));
+        assert_eq!(options.matching.report_format(), ReportFormat::Json);
+        assert_eq!(options.extraction.source_path_globs, vec!["main.cc"]);
+    }
+
+    #[test]
+    fn parse_options_extract() {
+        // Binary files aren't required
+        let options = CpplumberOptions::from_iter_safe([
+            PKG_NAME,
+            "extract",
+            "-o",
+            "artifacts.json",
+            "main.cc",
+            "-DDEF_TEST",
+        ])
+        .expect("from_iter_safe failed");
+
+        match options.command {
+            Some(Command::Extract {
+                output_file_path,
+                extraction,
+                ..
+            }) => {
+                assert_eq!(output_file_path, PathBuf::from("artifacts.json"));
+                assert_eq!(extraction.compile_definitions, vec!["DEF_TEST"]);
+                assert_eq!(extraction.source_path_globs, vec!["main.cc"]);
+            }
+            _ => panic!("Unexpected command: {:?}", options.command),
+        }
+    }
+
+    #[test]
+    fn parse_options_match() {
+        let options = CpplumberOptions::from_iter_safe([
+            PKG_NAME,
+            "match",
+            "-a",
+            "artifacts.json",
+            "--bin",
+            "a.out",
+        ])
+        .expect("from_iter_safe failed");
+
+        match options.command {
+            Some(Command::Match {
+                artifact_file_path,
+                matching,
+                ..
+            }) => {
+                assert_eq!(artifact_file_path, PathBuf::from("artifacts.json"));
+                assert_eq!(matching.binary_file_path_globs, vec!["a.out"]);
+            }
+            _ => panic!("Unexpected command: {:?}", options.command),
+        }
+        // Binary files are required
+        assert!(
+            CpplumberOptions::from_iter_safe([PKG_NAME, "match", "-a", "artifacts.json"]).is_err()
+        );
+    }
+
+    #[test]
+    fn parse_options_cache() {
+        let options = CpplumberOptions::from_iter_safe([
+            PKG_NAME,
+            "--bin",
+            "a.out",
+            "--cache-dir",
+            "cache",
+            "--no-cache",
+        ])
+        .expect("from_iter_safe failed");
+
+        assert_eq!(options.extraction.cache_dir, Some(PathBuf::from("cache")));
+        assert!(options.extraction.no_cache);
+    }
+
+    #[test]
+    fn parse_options_report_format() {
+        let options =
+            CpplumberOptions::from_iter_safe([PKG_NAME, "--bin", "a.out", "--format", "sarif"])
+                .expect("from_iter_safe failed");
+        assert_eq!(options.matching.report_format(), ReportFormat::Sarif);
+
+        let options = CpplumberOptions::from_iter_safe([PKG_NAME, "--bin", "a.out"])
+            .expect("from_iter_safe failed");
+        assert_eq!(options.matching.report_format(), ReportFormat::Text);
+        assert!(!options.matching.report_options().show_source);
+
+        let options =
+            CpplumberOptions::from_iter_safe([PKG_NAME, "--bin", "a.out", "--show-source"])
+                .expect("from_iter_safe failed");
+        assert!(options.matching.report_options().show_source);
+
+        let options =
+            CpplumberOptions::from_iter_safe([PKG_NAME, "--bin", "a.out", "--context-bytes", "16"])
+                .expect("from_iter_safe failed");
+        assert_eq!(options.matching.report_options().context_size, 16);
+
+        let options = CpplumberOptions::from_iter_safe([
+            PKG_NAME,
+            "--bin",
+            "a.out",
+            "--group-by",
+            "binary",
+            "--summary",
+        ])
+        .expect("from_iter_safe failed");
+        let report_options = options.matching.report_options();
+        assert_eq!(report_options.group_by, Some(LeakGrouping::Binary));
+        assert!(report_options.show_summary);
+        assert!(CpplumberOptions::from_iter_safe([
+            PKG_NAME,
+            "--bin",
+            "a.out",
+            "--group-by",
+            "section",
+        ])
+        .is_err());
+
+        // `--json` is a shorthand for `--format json`
+        assert!(CpplumberOptions::from_iter_safe([
+            PKG_NAME, "--bin", "a.out", "--json", "--format", "sarif",
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn parse_options_diff() {
+        let options =
+            CpplumberOptions::from_iter_safe([PKG_NAME, "diff", "old.json", "new.json", "--json"])
+                .expect("from_iter_safe failed");
+
+        match options.command {
+            Some(Command::Diff {
+                old_report_path,
+                new_report_path,
+                json_output,
+            }) => {
+                assert_eq!(old_report_path, PathBuf::from("old.json"));
+                assert_eq!(new_report_path, PathBuf::from("new.json"));
+                assert!(json_output);
+            }
+            _ => panic!("Unexpected command: {:?}", options.command),
+        }
+        // Both reports are required
+        assert!(CpplumberOptions::from_iter_safe([PKG_NAME, "diff", "old.json"]).is_err());
+    }
+}
@@ -0,0 +1,735 @@
+use std::{
+    collections::BTreeSet,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Mutex,
+    },
+};
+
+use anyhow::{anyhow, Context, Result};
+use clang::{Clang, Entity, EntityKind, Index, StorageClass, TranslationUnit};
+
+use crate::{
+    artifact_cache::ArtifactCache,
+    compilation_database::{CompileCommand, CompileCommands},
+    information_leak::PotentialLeak,
+};
+
+/// Options that control which artifacts are extracted from source files
+#[derive(Debug, Clone)]
+pub struct ExtractionOptions {
+    /// Ignore data declared in system headers
+    pub ignore_system_headers: bool,
+    /// Ignore string literals
+    pub ignore_string_literals: bool,
+    /// Ignore struct and class names
+    pub ignore_struct_names: bool,
+    /// Ignore function and method names
+    pub ignore_function_names: bool,
+    /// Ignore enum names
+    pub ignore_enum_names: bool,
+    /// Ignore enum constant names
+    pub ignore_enum_constant_names: bool,
+    /// Ignore names of global and static variables
+    pub ignore_variable_names: bool,
+    /// Ignore namespace names
+    pub ignore_namespace_names: bool,
+    /// Minimum size in bytes of the extracted artifacts
+    pub minimum_leak_size: usize,
+}
+
+impl Default for ExtractionOptions {
+    fn default() -> Self {
+        Self {
+            ignore_system_headers: true,
+            ignore_string_literals: false,
+            ignore_struct_names: false,
+            ignore_function_names: false,
+            ignore_enum_names: false,
+            ignore_enum_constant_names: false,
+            ignore_variable_names: false,
+            ignore_namespace_names: false,
+            minimum_leak_size: 4,
+        }
+    }
+}
+
+fn gather_entities_by_kind<'tu>(
+    root_entity: Entity<'tu>,
+    entity_kind_filter: &[EntityKind],
+    ignore_system_headers: bool,
+) -> Vec<Entity<'tu>> {
+    gather_entities_by_kind_rec(root_entity, entity_kind_filter, ignore_system_headers)
+}
+
+fn gather_entities_by_kind_rec<'tu>(
+    root_entity: Entity<'tu>,
+    entity_kind_filter: &[EntityKind],
+    ignore_system_headers: bool,
+) -> Vec<Entity<'tu>> {
+    let mut entities = vec![];
+
+    let root_entity_kind = root_entity.get_kind();
+    // Check the if entity's kind is one we're looking for
+    if entity_kind_filter
+        .iter()
+        .any(|elem| elem == &root_entity_kind)
+        && is_name_leakable(&root_entity)
+    {
+        entities.push(root_entity);
+    }
+
+    for child in root_entity.get_children() {
+        // Ignore entity if requested
+        if ignore_system_headers && child.is_in_system_header() {
+            continue;
+        }
+
+        let entities_sub =
+            gather_entities_by_kind_rec(child, entity_kind_filter, ignore_system_headers);
+        entities.extend(entities_sub);
+    }
+
+    entities
+}
+
+/// Returns false for declarations whose name cannot leak into binary files
+/// (i.e., local variables, anonymous namespaces and anonymous enums).
+fn is_name_leakable(entity: &Entity) -> bool {
+    match entity.get_kind() {
+        EntityKind::VarDecl => has_static_storage(entity),
+        // Anonymous enums have no name, but libclang gives them a display name
+        // (e.g., "(unnamed enum at main.cc:3:1)"). Their constants are still
+        // extracted.
+        EntityKind::Namespace | EntityKind::EnumDecl => !entity.is_anonymous(),
+        _ => true,
+    }
+}
+
+/// Returns true if a variable has a static storage duration (i.e., global
+/// variables, static data members and static local variables).
+fn has_static_storage(entity: &Entity) -> bool {
+    match entity.get_storage_class() {
+        Some(StorageClass::Static | StorageClass::Extern | StorageClass::PrivateExtern) => true,
+        // Variables without storage class specifier have a static storage
+        // duration if they're declared outside of functions
+        _ => matches!(
+            entity.get_semantic_parent().map(|parent| parent.get_kind()),
+            Some(
+                EntityKind::TranslationUnit
+                    | EntityKind::Namespace
+                    | EntityKind::LinkageSpec
+                    | EntityKind::StructDecl
+                    | EntityKind::ClassDecl
+                    | EntityKind::UnionDecl
+                    | EntityKind::ClassTemplate
+                    | EntityKind::ClassTemplatePartialSpecialization
+            )
+        ),
+    }
+}
+
+/// Wrapper that allows sharing the `Clang` instance between worker threads.
+struct SharedClang(Clang);
+
+// SAFETY: `Clang` is a stateless token. Sharing it only lets workers create
+// their own `Index`, and libclang supports concurrent use of distinct indexes as
+// long as each one (and everything parsed with it) stays on the thread that
+// created it, which `Index` being `!Send` guarantees. This requires libclang to
+// be linked, rather than loaded for a single thread by clang-sys's "runtime"
+// feature, which `clang_sys_linking_check` rejects at compile time.
+unsafe impl Sync for SharedClang {}
+
+impl SharedClang {
+    fn get(&self) -> &Clang {
+        &self.0
+    }
+}
+
+/// Fails to compile if clang-sys loads libclang at runtime (e.g., if another
+/// crate of the build enables its "runtime" feature): `clang_sys::load` only
+/// exists in that case, and then makes `load` ambiguous.
+mod clang_sys_linking_check {
+    #[allow(unused_imports)]
+    use self::linked::*;
+    #[allow(unused_imports)]
+    use clang_sys::*;
+
+    mod linked {
+        #[allow(non_upper_case_globals)]
+        pub const load: () = ();
+    }
+
+    const _: () = load;
+}
+
+/// Parses source files in parallel and extracts information that could leak
+/// from them. Artifacts are returned in the same order as the compile
+/// commands.
+///
+/// `use_file_path_from_arguments` should be set to the value returned by
+/// [`CompilationDatabase::is_file_path_in_arguments`] for the database the
+/// compile commands come from. If an `artifact_cache` is given, unchanged
+/// files aren't parsed again.
+///
+/// [`CompilationDatabase::is_file_path_in_arguments`]: crate::compilation_database::CompilationDatabase::is_file_path_in_arguments
+///
+/// # Example
+///
+/// ```no_run
+/// use std::path::PathBuf;
+///
+/// use cpplumber::compilation_database::{CompilationDatabase, FileListDatabase};
+/// use cpplumber::extraction::{extract_artifacts_from_source_files, ExtractionOptions};
+///
+/// let database = FileListDatabase::new(&[PathBuf::from("main.cc")], vec!["-DNDEBUG".to_string()]);
+/// let potential_leaks = extract_artifacts_from_source_files(
+///     database.get_all_compile_commands()?,
+///     database.is_file_path_in_arguments(),
+///     &ExtractionOptions::default(),
+///     None,
+/// )?;
+/// for leak in &potential_leaks {
+///     println!("{} ({:?})", leak.data, leak.data_type);
+/// }
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+pub fn extract_artifacts_from_source_files(
+    compile_commands: CompileCommands,
+    use_file_path_from_arguments: bool,
+    options: &ExtractionOptions,
+    artifact_cache: Option<&ArtifactCache>,
+) -> Result<Vec<PotentialLeak>> {
+    let clang = SharedClang(Clang::new().map_err(|e| anyhow!(e))?);
+
+    // Setup filter
+    let mut entity_kind_filter = vec![];
+    if !options.ignore_string_literals {
+        entity_kind_filter.push(EntityKind::StringLiteral);
+    }
+    if !options.ignore_struct_names {
+        entity_kind_filter.push(EntityKind::StructDecl);
+        entity_kind_filter.push(EntityKind::ClassDecl);
+        entity_kind_filter.push(EntityKind::ClassTemplate);
+    }
+    if !options.ignore_function_names {
+        entity_kind_filter.push(EntityKind::FunctionDecl);
+        entity_kind_filter.push(EntityKind::Method);
+    }
+    if !options.ignore_enum_names {
+        entity_kind_filter.push(EntityKind::EnumDecl);
+    }
+    if !options.ignore_enum_constant_names {
+        entity_kind_filter.push(EntityKind::EnumConstantDecl);
+    }
+    if !options.ignore_variable_names {
+        entity_kind_filter.push(EntityKind::VarDecl);
+    }
+    if !options.ignore_namespace_names {
+        entity_kind_filter.push(EntityKind::Namespace);
+    }
+
+    // Parse source files in parallel, with one worker per thread of the
+    // thread pool. Each worker uses its own clang index, and results are kept
+    // in the same order as the compile commands, so that the output is
+    // deterministic.
+    let next_command_index = AtomicUsize::new(0);
+    let has_failed = AtomicBool::new(false);
+    let results: Mutex<Vec<(usize, Result<Vec<PotentialLeak>>)>> = Mutex::default();
+    let worker_count = rayon::current_num_threads().min(compile_commands.len());
+    rayon::scope(|scope| {
+        for _ in 0..worker_count {
+            scope.spawn(|_| {
+                let index = Index::new(clang.get(), false, false);
+                let mut worker_results = vec![];
+                // Stop early if another worker failed, as the whole
+                // extraction fails anyway
+                while !has_failed.load(Ordering::Relaxed) {
+                    let command_index = next_command_index.fetch_add(1, Ordering::Relaxed);
+                    let compile_cmd = match compile_commands.get(command_index) {
+                        Some(compile_cmd) => compile_cmd,
+                        None => break,
+                    };
+
+                    let result = load_or_extract_artifacts(
+                        &index,
+                        compile_cmd,
+                        use_file_path_from_arguments,
+                        &entity_kind_filter,
+                        options,
+                        artifact_cache,
+                    );
+                    if result.is_err() {
+                        has_failed.store(true, Ordering::Relaxed);
+                    }
+                    worker_results.push((command_index, result));
+                }
+                results.lock().unwrap().extend(worker_results);
+            });
+        }
+    });
+
+    let mut results = results.into_inner().unwrap();
+    results.sort_by_key(|(command_index, _)| *command_index);
+    let potential_leaks_per_file = results
+        .into_iter()
+        .map(|(_, result)| result)
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(potential_leaks_per_file.into_iter().flatten().collect())
+}
+
+/// Returns the artifacts of a single source file, from `artifact_cache` if
+/// possible, or by parsing the file otherwise.
+fn load_or_extract_artifacts(
+    index: &Index,
+    compile_cmd: &CompileCommand,
+    use_file_path_from_arguments: bool,
+    entity_kind_filter: &[EntityKind],
+    options: &ExtractionOptions,
+    artifact_cache: Option<&ArtifactCache>,
+) -> Result<Vec<PotentialLeak>> {
+    // Reuse artifacts from previous runs if possible
+    if let Some(potential_leaks) = artifact_cache.and_then(|cache| cache.load(compile_cmd)) {
+        log::debug!(
+            "Using cached artifacts for '{}'",
+            compile_cmd.filename.display()
+        );
+        return Ok(potential_leaks);
+    }
+
+    let (potential_leaks, dependencies) = extract_artifacts_from_source_file(
+        index,
+        compile_cmd,
+        use_file_path_from_arguments,
+        entity_kind_filter,
+        options.ignore_system_headers,
+        options.minimum_leak_size,
+    )?;
+    if let (Some(cache), Some(dependencies)) = (artifact_cache, dependencies) {
+        if let Err(err) = cache.store(compile_cmd, &dependencies, &potential_leaks) {
+            log::warn!(
+                "Failed to cache artifacts for '{}': {}",
+                compile_cmd.filename.display(),
+                err
+            );
+        }
+    }
+
+    Ok(potential_leaks)
+}
+
+/// Extracts artifacts from a single source file. Also returns the files the
+/// translation unit depends on, if they could be determined.
+fn extract_artifacts_from_source_file(
+    index: &Index,
+    compile_cmd: &CompileCommand,
+    use_file_path_from_arguments: bool,
+    entity_kind_filter: &[EntityKind],
+    ignore_system_headers: bool,
+    minimum_leak_size: usize,
+) -> Result<(Vec<PotentialLeak>, Option<Vec<PathBuf>>)> {
+    // Note: For some reason, having the file path in `arguments` when
+    // passing the file path explicitly to libclang make the parser fail.
+    // So we explicitely avoid doing so.
+    let file_path = if use_file_path_from_arguments {
+        PathBuf::default()
+    } else {
+        compile_cmd.filename.clone()
+    };
+    let translation_unit = index
+        .parser(&file_path)
+        .arguments(&compile_cmd.arguments)
+        .parse()
+        .with_context(|| format!("Failed to parse source file '{}'", file_path.display()))?;
+
+    // Gather entities
+    let entities = gather_entities_by_kind(
+        translation_unit.get_entity(),
+        entity_kind_filter,
+        ignore_system_headers,
+    );
+
+    let potential_leaks = entities
+        .into_iter()
+        .filter_map(|entity| {
+            let leak_res: Result<PotentialLeak> = entity.try_into();
+            if let Ok(potential_leak) = leak_res {
+                if potential_leak.bytes.len() >= minimum_leak_size {
+                    Some(potential_leak)
+                } else {
+                    // Value is too small, ignore it
+                    None
+                }
+            } else {
+                // Log failure and discard element
+                log::warn!(
+                    "Failed to process entity '{:?}': {}",
+                    entity,
+                    leak_res.unwrap_err()
+                );
+                None
+            }
+        })
+        .collect();
+
+    Ok((
+        potential_leaks,
+        gather_translation_unit_dependencies(&translation_unit),
+    ))
+}
+
+/// Returns the paths of the main file of a translation unit and of all the
+/// files it includes (directly or not).
+fn gather_translation_unit_dependencies(
+    translation_unit: &TranslationUnit,
+) -> Option<Vec<PathBuf>> {
+    let main_file = translation_unit
+        .get_entity()
+        .get_name()
+        .and_then(|main_file_path| translation_unit.get_file(main_file_path))?;
+
+    let mut dependencies = BTreeSet::new();
+    let mut files_to_visit = vec![main_file];
+    while let Some(file) = files_to_visit.pop() {
+        let file_path = file.get_path();
+        let file_path = file_path.canonicalize().unwrap_or(file_path);
+        if dependencies.insert(file_path) {
+            files_to_visit.extend(
+                file.get_includes()
+                    .into_iter()
+                    .filter_map(|include| include.get_file()),
+            );
+        }
+    }
+
+    Some(dependencies.into_iter().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{
+        compilation_database::{CompilationDatabase, FileListDatabase},
+        information_leak::{CxxAbi, LeakedDataForm, LeakedDataType},
+    };
+
+    use super::*;
+
+    use serial_test::serial;
+
+    const FILE_LIST_PROJ_PATH: &str = "tests/data/main/file_list_proj";
+
+    /// Returns the names of the given type, in extraction order
+    fn names_of_type(potential_leaks: &[PotentialLeak], data_type: LeakedDataType) -> Vec<&str> {
+        potential_leaks
+            .iter()
+            .filter(|leak| leak.data_type == data_type)
+            .map(|leak| leak.data.as_str())
+            .collect()
+    }
+
+    #[test]
+    #[serial]
+    fn extract_artifacts_from_source_files_file_list() {
+        let root_dir_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(FILE_LIST_PROJ_PATH);
+        let file_list_db = FileListDatabase::new(
+            &[root_dir_path.join("main.cc")],
+            vec![
+                "-DDEF_TEST".to_string(),
+                format!("-I{}", FILE_LIST_PROJ_PATH),
+            ],
+        );
+        let potential_leaks = extract_artifacts_from_source_files(
+            file_list_db
+                .get_all_compile_commands()
+                .expect("get_all_compile_commands failed"),
+            file_list_db.is_file_path_in_arguments(),
+            &ExtractionOptions {
+                minimum_leak_size: 0,
+                ..Default::default()
+            },
+            None,
+        )
+        .expect("extract_artifacts_from_source_files failed");
+
+        let expected_string_literals = vec![
+            "included_string_literal",
+            "included_string_literal",
+            "my_c_string",
+            "c_string",
+            "my_utf8_string",
+            "utf8_string",
+            "my_wide_string",
+            "wide_string",
+            "my_utf16_string",
+            "utf16_string",
+            "my_utf32_string",
+            "utf32_string",
+            "my_raw_string",
+            "raw_string",
+            "my_raw_utf8_string",
+            "raw_utf8_string",
+            "my_wide_raw_string",
+            "wide_raw_string",
+            "my_raw_utf16_string",
+            "raw_utf16_string",
+            "my_raw_utf32_string",
+            "raw_utf32_string",
+            "def_test_string",
+            "def_test",
+            "my_concatenated_string",
+            "concatenated_string",
+            "my_multiline_string",
+            r#"multiline\nstring"#,
+            "my_escaped_string",
+            r#"'\"\n\t\a\b|\220|\220|\351\246\231|\351\246\231|\360\237\230\202"#,
+            "MyStruct",
+            "",
+            "MyClass",
+            "",
+            "my_method",
+            "my_static_member",
+            "my_method",
+            "my_static_member",
+            "MyEnum",
+            "MyEnumConstant",
+            "MyEnumClass",
+            "MyEnumClassConstant",
+            "UnnamedEnumConstant",
+            "my_namespace",
+            "my_namespaced_variable",
+            "anonymous_namespace_variable",
+            "main",
+            r#"%s\n"#,
+            "preprocessor_string_literal",
+            r#"%s\n"#,
+            "preprocessor_string_literal",
+            r#"%s\n"#,
+            "my_static_local",
+        ];
+
+        // Check extracted string literals
+        assert!(potential_leaks.iter().enumerate().all(|(i, leak)| {
+            println!("{:?}", leak.data);
+            *leak.data == expected_string_literals[i]
+        }));
+        assert_eq!(expected_string_literals.len(), potential_leaks.len());
+
+        // Check the types of extracted names
+        assert_eq!(
+            names_of_type(&potential_leaks, LeakedDataType::FunctionName),
+            ["my_method", "my_method", "main"]
+        );
+        assert_eq!(
+            names_of_type(&potential_leaks, LeakedDataType::EnumName),
+            ["MyEnum", "MyEnumClass"]
+        );
+        assert_eq!(
+            names_of_type(&potential_leaks, LeakedDataType::EnumConstantName),
+            [
+                "MyEnumConstant",
+                "MyEnumClassConstant",
+                "UnnamedEnumConstant"
+            ]
+        );
+        assert_eq!(
+            names_of_type(&potential_leaks, LeakedDataType::VariableName),
+            [
+                "included_string_literal",
+                "my_c_string",
+                "my_utf8_string",
+                "my_wide_string",
+                "my_utf16_string",
+                "my_utf32_string",
+                "my_raw_string",
+                "my_raw_utf8_string",
+                "my_wide_raw_string",
+                "my_raw_utf16_string",
+                "my_raw_utf32_string",
+                "def_test_string",
+                "my_concatenated_string",
+                "my_multiline_string",
+                "my_escaped_string",
+                "my_static_member",
+                "my_static_member",
+                "my_namespaced_variable",
+                "anonymous_namespace_variable",
+                "my_static_local",
+            ]
+        );
+        // Anonymous namespaces are skipped
+        assert_eq!(
+            names_of_type(&potential_leaks, LeakedDataType::NamespaceName),
+            ["my_namespace"]
+        );
+        // Mangled names are looked for in both ABIs (translation units target
+        // the host, which uses the Itanium ABI)
+        let my_method = potential_leaks
+            .iter()
+            .find(|leak| *leak.data == "my_method")
+            .unwrap();
+        assert_eq!(
+            my_method.alternative_forms,
+            [
+                LeakedDataForm::MangledName {
+                    abi: CxxAbi::Itanium,
+                    name: Arc::new("_ZNK7MyClass9my_methodEi".to_string()),
+                },
+                LeakedDataForm::MangledName {
+                    abi: CxxAbi::Msvc,
+                    name: Arc::new("?my_method@MyClass@@".to_string()),
+                },
+            ]
+        );
+        // Local variables and parameters are skipped
+        for name in [
+            "my_parameter",
+            "my_local_variable",
+            "struct_ptr",
+            "class_ptr",
+        ] {
+            assert!(potential_leaks.iter().all(|leak| *leak.data != name));
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn extract_artifacts_from_multiple_source_files_in_order() {
+        let root_dir_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(FILE_LIST_PROJ_PATH);
+        // Note: File lists are sorted by path
+        let source_file_paths = [
+            root_dir_path.join("header.h"),
+            root_dir_path.join("main.cc"),
+        ];
+        let arguments = vec![
+            "-DDEF_TEST".to_string(),
+            format!("-I{}", FILE_LIST_PROJ_PATH),
+        ];
+        let extract_artifacts = |source_file_paths: &[PathBuf]| {
+            let file_list_db = FileListDatabase::new(source_file_paths, arguments.clone());
+            extract_artifacts_from_source_files(
+                file_list_db
+                    .get_all_compile_commands()
+                    .expect("get_all_compile_commands failed"),
+                file_list_db.is_file_path_in_arguments(),
+                &ExtractionOptions {
+                    minimum_leak_size: 0,
+                    ..Default::default()
+                },
+                None,
+            )
+            .expect("extract_artifacts_from_source_files failed")
+        };
+
+        // Files are parsed in parallel, but artifacts must be returned in the
+        // same order as the source files
+        let potential_leaks = extract_artifacts(&source_file_paths);
+        let expected_potential_leaks: Vec<_> = source_file_paths
+            .iter()
+            .flat_map(|source_file_path| extract_artifacts(std::slice::from_ref(source_file_path)))
+            .collect();
+        let leak_summary = |leak: &PotentialLeak| {
+            (
+                leak.data.clone(),
+                leak.declaration_metadata.file.clone(),
+                leak.declaration_metadata.line,
+            )
+        };
+        assert_eq!(
+            potential_leaks.iter().map(leak_summary).collect::<Vec<_>>(),
+            expected_potential_leaks
+                .iter()
+                .map(leak_summary)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn extract_artifacts_with_minimum_leak_size() {
+        let root_dir_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(FILE_LIST_PROJ_PATH);
+        let file_list_db = FileListDatabase::new(
+            &[root_dir_path.join("main.cc")],
+            vec![
+                "-DDEF_TEST".to_string(),
+                format!("-I{}", FILE_LIST_PROJ_PATH),
+            ],
+        );
+        let potential_leaks = extract_artifacts_from_source_files(
+            file_list_db
+                .get_all_compile_commands()
+                .expect("get_all_compile_commands failed"),
+            file_list_db.is_file_path_in_arguments(),
+            &ExtractionOptions {
+                minimum_leak_size: 4,
+                ..Default::default()
+            },
+            None,
+        )
+        .expect("extract_artifacts_from_source_files failed");
+
+        // r#""%s\n""# should be removed
+        let expected_string_literals = vec![
+            // main.cc
+            "included_string_literal",
+            "included_string_literal",
+            "my_c_string",
+            "c_string",
+            "my_utf8_string",
+            "utf8_string",
+            "my_wide_string",
+            "wide_string",
+            "my_utf16_string",
+            "utf16_string",
+            "my_utf32_string",
+            "utf32_string",
+            "my_raw_string",
+            "raw_string",
+            "my_raw_utf8_string",
+            "raw_utf8_string",
+            "my_wide_raw_string",
+            "wide_raw_string",
+            "my_raw_utf16_string",
+            "raw_utf16_string",
+            "my_raw_utf32_string",
+            "raw_utf32_string",
+            "def_test_string",
+            "def_test",
+            "my_concatenated_string",
+            "concatenated_string",
+            "my_multiline_string",
+            r#"multiline\nstring"#,
+            "my_escaped_string",
+            r#"'\"\n\t\a\b|\220|\220|\351\246\231|\351\246\231|\360\237\230\202"#,
+            "MyStruct",
+            "MyClass",
+            "my_method",
+            "my_static_member",
+            "my_method",
+            "my_static_member",
+            "MyEnum",
+            "MyEnumConstant",
+            "MyEnumClass",
+            "MyEnumClassConstant",
+            "UnnamedEnumConstant",
+            "my_namespace",
+            "my_namespaced_variable",
+            "anonymous_namespace_variable",
+            "main",
+            "preprocessor_string_literal",
+            r#"%s\n"#,
+            "preprocessor_string_literal",
+            "my_static_local",
+        ];
+
+        // Check extracted string literals
+        assert!(potential_leaks.iter().enumerate().all(|(i, leak)| {
+            println!("{:?}", leak.data);
+            *leak.data == expected_string_literals[i]
+        }));
+        assert_eq!(expected_string_literals.len(), potential_leaks.len());
+    }
+}
@@ -1,12 +1,16 @@
+#[cfg(test)]
+use std::path::PathBuf;
 use std::{ops::Deref, sync::Arc};
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
+#[cfg(test)]
+use super::{BinaryLocation, SourceLocation};
 use super::{LeakLocation, LeakedDataType};
 
 /// Struct containing information on a piece of data that has leaked into a
 /// binary file.
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize)]
 pub struct ConfirmedLeak {
     /// Type of data leaked
     pub data_type: LeakedDataType,
@@ -17,6 +21,29 @@ pub struct ConfirmedLeak {
     pub location: LeakLocation,
 }
 
+#[cfg(test)]
+impl ConfirmedLeak {
+    /// Builds a leak of `data`, declared at `source_file:line` and found at
+    /// `offset` in `binary_file`, used to test reporting
+    pub(crate) fn new(
+        data_type: LeakedDataType,
+        data: &str,
+        source_file: impl Into<PathBuf>,
+        line: u64,
+        binary_file: impl Into<PathBuf>,
+        offset: u64,
+    ) -> Self {
+        Self {
+            data_type,
+            data: Arc::new(data.to_string()),
+            location: LeakLocation {
+                source: Arc::new(SourceLocation::new(source_file, line)),
+                binary: BinaryLocation::new(binary_file, offset, data.len() as u64),
+            },
+        }
+    }
+}
+
 impl From<ConfirmedLeakWithUniqueLocation> for ConfirmedLeak {
     fn from(leak: ConfirmedLeakWithUniqueLocation) -> Self {
         leak.0
@@ -57,7 +84,7 @@ impl Eq for ConfirmedLeakWithUniqueLocation {}
 
 impl PartialOrd for ConfirmedLeakWithUniqueLocation {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.0.location.partial_cmp(&other.0.location)
+        Some(self.cmp(other))
     }
 }
 
@@ -95,7 +122,7 @@ impl Eq for ConfirmedLeakWithUniqueValue {}
 
 impl PartialOrd for ConfirmedLeakWithUniqueValue {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.0.data.partial_cmp(&other.0.data)
+        Some(self.cmp(other))
     }
 }
 
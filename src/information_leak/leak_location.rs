@@ -1,22 +1,93 @@
 use std::{hash::Hash, path::PathBuf, sync::Arc};
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+
+use super::LeakedDataForm;
 
 /// Struct containing the source and binary locations of leaked data
-#[derive(Serialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct LeakLocation {
     pub source: Arc<SourceLocation>,
     pub binary: BinaryLocation,
 }
 
-#[derive(Debug, Serialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct SourceLocation {
     pub file: PathBuf,
     pub line: u64,
+    /// Columns spanned by the declaration on `line` (unknown in reports
+    /// generated by older versions)
+    #[serde(default)]
+    pub columns: Option<ColumnRange>,
+}
+
+impl SourceLocation {
+    /// Builds a `SourceLocation` pointing to `line` in `file`, with unknown
+    /// columns
+    pub fn new(file: impl Into<PathBuf>, line: u64) -> Self {
+        Self {
+            file: file.into(),
+            line,
+            columns: None,
+        }
+    }
 }
 
-#[derive(Serialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+/// Range of columns on a line of source code. Columns are 1-based and the end
+/// of the range is excluded.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ColumnRange {
+    pub start: u64,
+    pub end: u64,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct BinaryLocation {
     pub file: Arc<PathBuf>,
     pub offset: u64,
+    /// Size in bytes of the leaked data (unknown in reports generated by older
+    /// versions)
+    #[serde(default)]
+    pub size: u64,
+    /// Form of the leaked data found at this location
+    #[serde(default, skip_serializing_if = "LeakedDataForm::is_plain")]
+    pub form: LeakedDataForm,
+    /// Architecture of the slice containing the leaked data (universal
+    /// binaries only)
+    pub architecture: Option<Arc<String>>,
+    /// Name of the archive member containing the leaked data (static
+    /// libraries only)
+    pub archive_member: Option<Arc<String>>,
+    /// Name of the section containing the leaked data, if any
+    pub section: Option<Arc<String>>,
+    /// Name of the segment containing the leaked data, if any
+    pub segment: Option<Arc<String>>,
+    /// Virtual address of the leaked data, if it's loaded in memory
+    pub virtual_address: Option<u64>,
+    /// Virtual address of the leaked data, relative to the image's base
+    /// address (PE files only)
+    pub relative_virtual_address: Option<u64>,
+    /// Special data directory containing the leaked data, if any (e.g., PE
+    /// resources)
+    pub data_directory: Option<Arc<String>>,
+}
+
+impl BinaryLocation {
+    /// Builds a `BinaryLocation` for `size` bytes of plain data found at
+    /// `offset` in `file`, without any information on the file's layout
+    pub fn new(file: impl Into<PathBuf>, offset: u64, size: u64) -> Self {
+        Self {
+            file: Arc::new(file.into()),
+            offset,
+            size,
+            form: LeakedDataForm::Plain,
+            architecture: None,
+            archive_member: None,
+            section: None,
+            segment: None,
+            virtual_address: None,
+            relative_virtual_address: None,
+            data_directory: None,
+        }
+    }
 }
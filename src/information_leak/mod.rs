@@ -6,10 +6,17 @@ pub use confirmed_leak::*;
 pub use leak_location::*;
 pub use potential_leak::*;
 
-use serde::Serialize;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+/// Placeholder for hashes generated by compilers in the representations of
+/// alternative forms (e.g., the hash identifying an anonymous namespace in MSVC
+/// RTTI names), which matches any hexadecimal number
+pub const GENERATED_HASH_PLACEHOLDER: char = '*';
 
 /// Describes the kind of data that's leaked
-#[derive(Debug, Serialize, Clone, Copy)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum LeakedDataType {
     /// Data comes from a string literal
     StringLiteral,
@@ -17,4 +24,70 @@ pub enum LeakedDataType {
     StructName,
     /// Data represents the name of a C++ class
     ClassName,
+    /// Data represents the name of a C/C++ function or C++ method
+    FunctionName,
+    /// Data represents the name of a C/C++ enum
+    EnumName,
+    /// Data represents the name of a C/C++ enum constant
+    EnumConstantName,
+    /// Data represents the name of a C/C++ global or static variable
+    VariableName,
+    /// Data represents the name of a C++ namespace
+    NamespaceName,
+}
+
+/// C++ ABI, which defines how names are mangled
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CxxAbi {
+    /// Itanium C++ ABI (e.g., used by GCC and Clang, except for Windows MSVC
+    /// targets)
+    Itanium,
+    /// Microsoft C++ ABI (i.e., used by MSVC)
+    Msvc,
+}
+
+/// Describes the form in which leaked data is found in binary files
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum LeakedDataForm {
+    /// Data is found as represented in the source code (i.e., as described by
+    /// `PotentialLeak::bytes`)
+    #[default]
+    Plain,
+    /// Data is found as part of the given symbol name, mangled following `abi`.
+    /// Names of potential leaks may only be the prefix shared by the mangled
+    /// names of all overloads (i.e., without the parameter types).
+    MangledName { abi: CxxAbi, name: Arc<String> },
+    /// Data is found as part of the given RTTI name (i.e., the type info name
+    /// or type descriptor name generated for a struct or class). Names of
+    /// potential leaks may contain `GENERATED_HASH_PLACEHOLDER`.
+    RttiName(Arc<String>),
+}
+
+impl LeakedDataForm {
+    /// Returns true if the data is found as represented in the source code
+    pub fn is_plain(&self) -> bool {
+        matches!(self, Self::Plain)
+    }
+
+    /// Returns the representation of the data in this form, or `None` for
+    /// `LeakedDataForm::Plain` (see `PotentialLeak::bytes`)
+    pub fn representation(&self) -> Option<&str> {
+        match self {
+            Self::Plain => None,
+            Self::MangledName { name, .. } | Self::RttiName(name) => Some(name),
+        }
+    }
+
+    /// Returns the same form, with another representation (e.g., with the
+    /// generated hashes found in a binary file)
+    pub fn with_representation(&self, representation: String) -> Self {
+        match self {
+            Self::Plain => Self::Plain,
+            Self::MangledName { abi, .. } => Self::MangledName {
+                abi: *abi,
+                name: Arc::new(representation),
+            },
+            Self::RttiName(_) => Self::RttiName(Arc::new(representation)),
+        }
+    }
 }
@@ -1,14 +1,22 @@
-use std::{borrow::Cow, hash::Hash, sync::Arc};
+#[cfg(feature = "extraction")]
+use std::{borrow::Cow, collections::BTreeSet, path::Path};
+use std::{hash::Hash, sync::Arc};
 
+#[cfg(feature = "extraction")]
 use anyhow::{anyhow, Result};
-use clang::{Entity, EntityKind};
+#[cfg(feature = "extraction")]
+use clang::{source::SourceRange, Entity, EntityKind, Language};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "extraction")]
 use widestring::{encode_utf16, encode_utf32};
 
-use super::{LeakedDataType, SourceLocation};
+#[cfg(feature = "extraction")]
+use super::{ColumnRange, CxxAbi, GENERATED_HASH_PLACEHOLDER};
+use super::{LeakedDataForm, LeakedDataType, SourceLocation};
 
 /// Struct containing information on a piece of data from the source code, which
 /// may leak into a binary file.
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PotentialLeak {
     /// Type of data leaked
     pub data_type: LeakedDataType,
@@ -19,8 +27,13 @@ pub struct PotentialLeak {
     pub bytes: Vec<u8>,
     /// Information on where the leaked data is declared in the source code
     pub declaration_metadata: Arc<SourceLocation>,
+    /// Other forms the leaked data may take in binary files (e.g., mangled
+    /// names), looked for in addition to `bytes`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub alternative_forms: Vec<LeakedDataForm>,
 }
 
+#[cfg(feature = "extraction")]
 impl TryFrom<Entity<'_>> for PotentialLeak {
     type Error = anyhow::Error;
 
@@ -48,33 +61,444 @@ impl TryFrom<Entity<'_>> for PotentialLeak {
                     declaration_metadata: Arc::new(SourceLocation {
                         file: file_location.canonicalize()?,
                         line: location.line as u64,
+                        columns: entity
+                            .get_range()
+                            .and_then(|range| column_range(range, location.line)),
                     }),
+                    alternative_forms: vec![],
                 })
             }
-            entity_kind @ (EntityKind::StructDecl | EntityKind::ClassDecl) => {
+            entity_kind @ (EntityKind::StructDecl
+            | EntityKind::ClassDecl
+            | EntityKind::ClassTemplate) => {
+                let is_template = entity_kind == EntityKind::ClassTemplate;
+                let record_kind = if is_template {
+                    entity
+                        .get_template_kind()
+                        .ok_or_else(|| anyhow!("Failed to get template's kind"))?
+                } else {
+                    entity_kind
+                };
                 // Convert `EntityKind` to `LeakedDataType`
-                let data_type = match entity_kind {
+                let data_type = match record_kind {
                     EntityKind::StructDecl => LeakedDataType::StructName,
                     EntityKind::ClassDecl => LeakedDataType::ClassName,
+                    _ => return Err(anyhow!("Unsupported template kind")),
+                };
+                // Note: Display names of class templates contain their parameters
+                let leaked_information = if is_template {
+                    entity.get_name().unwrap_or_default()
+                } else {
+                    entity.get_display_name().unwrap_or_default()
+                };
+                let rtti_names = rtti_names(
+                    &entity,
+                    &leaked_information,
+                    data_type == LeakedDataType::StructName,
+                    is_template,
+                );
+
+                Self::from_declaration_name(
+                    entity,
+                    data_type,
+                    leaked_information,
+                    rtti_names,
+                    &file_location,
+                    location.line,
+                )
+            }
+            EntityKind::FunctionDecl | EntityKind::Method => {
+                // Note: Display names of functions contain their parameters
+                let leaked_information = entity
+                    .get_name()
+                    .ok_or_else(|| anyhow!("Failed to get entity's name"))?;
+                let mangled_names = mangled_names(&entity, &leaked_information);
+
+                Self::from_declaration_name(
+                    entity,
+                    LeakedDataType::FunctionName,
+                    leaked_information,
+                    mangled_names,
+                    &file_location,
+                    location.line,
+                )
+            }
+            entity_kind @ (EntityKind::EnumDecl | EntityKind::EnumConstantDecl) => {
+                // Convert `EntityKind` to `LeakedDataType`
+                let data_type = match entity_kind {
+                    EntityKind::EnumDecl => LeakedDataType::EnumName,
+                    EntityKind::EnumConstantDecl => LeakedDataType::EnumConstantName,
                     _ => unreachable!("This entity kind should not be matched"),
                 };
                 let leaked_information = entity.get_display_name().unwrap_or_default();
 
-                Ok(Self {
+                Self::from_declaration_name(
+                    entity,
                     data_type,
-                    bytes: leaked_information.as_bytes().to_vec(),
-                    data: Arc::new(leaked_information),
-                    declaration_metadata: Arc::new(SourceLocation {
-                        file: file_location.canonicalize()?,
-                        line: location.line as u64,
-                    }),
-                })
+                    leaked_information,
+                    vec![],
+                    &file_location,
+                    location.line,
+                )
+            }
+            EntityKind::VarDecl => {
+                let leaked_information = entity
+                    .get_name()
+                    .ok_or_else(|| anyhow!("Failed to get entity's name"))?;
+                let mangled_names = mangled_names(&entity, &leaked_information);
+
+                Self::from_declaration_name(
+                    entity,
+                    LeakedDataType::VariableName,
+                    leaked_information,
+                    mangled_names,
+                    &file_location,
+                    location.line,
+                )
+            }
+            EntityKind::Namespace => {
+                let leaked_information = entity
+                    .get_name()
+                    .ok_or_else(|| anyhow!("Failed to get entity's name"))?;
+
+                Self::from_declaration_name(
+                    entity,
+                    LeakedDataType::NamespaceName,
+                    leaked_information,
+                    vec![],
+                    &file_location,
+                    location.line,
+                )
             }
             _ => Err(anyhow!("Unsupported entity kind")),
         }
     }
 }
 
+/// Returns the columns spanned by `range` on the given line. Ranges spanning
+/// several lines are truncated to their first column.
+#[cfg(feature = "extraction")]
+fn column_range(range: SourceRange, line: u32) -> Option<ColumnRange> {
+    let start = range.get_start().get_file_location();
+    let end = range.get_end().get_file_location();
+    // Ranges starting on another line (e.g., in macro expansions) are ignored
+    if start.line != line {
+        return None;
+    }
+
+    let end_column = if end.line == start.line && end.column > start.column {
+        end.column
+    } else {
+        start.column + 1
+    };
+
+    Some(ColumnRange {
+        start: start.column as u64,
+        end: end_column as u64,
+    })
+}
+
+#[cfg(feature = "extraction")]
+impl PotentialLeak {
+    /// Creates a `PotentialLeak` for the name of a declaration, declared at
+    /// the given line of `file_path`
+    fn from_declaration_name(
+        entity: Entity,
+        data_type: LeakedDataType,
+        name: String,
+        alternative_forms: Vec<LeakedDataForm>,
+        file_path: &Path,
+        line: u32,
+    ) -> Result<Self> {
+        Ok(Self {
+            data_type,
+            bytes: name.as_bytes().to_vec(),
+            data: Arc::new(name),
+            declaration_metadata: Arc::new(SourceLocation {
+                file: file_path.canonicalize()?,
+                line: line as u64,
+                columns: entity
+                    .get_name_ranges()
+                    .first()
+                    .and_then(|range| column_range(*range, line)),
+            }),
+            alternative_forms,
+        })
+    }
+}
+
+/// Returns the mangled names of a function, method or variable which differ
+/// from its `name`. libclang mangles names following the ABI of the translation
+/// unit's target (e.g., Itanium for Linux targets, MSVC for Windows MSVC
+/// targets). For the other ABI, the prefix shared by the mangled names of all
+/// overloads is derived from the declaration's scopes.
+#[cfg(feature = "extraction")]
+fn mangled_names(entity: &Entity, name: &str) -> Vec<LeakedDataForm> {
+    let target_abi = if entity
+        .get_translation_unit()
+        .get_target()
+        .triple
+        .contains("msvc")
+    {
+        CxxAbi::Msvc
+    } else {
+        CxxAbi::Itanium
+    };
+    // Constructors and destructors have several mangled names
+    let mut mangled_names: BTreeSet<String> = entity
+        .get_mangled_names()
+        .unwrap_or_default()
+        .into_iter()
+        .collect();
+    mangled_names.extend(entity.get_mangled_name());
+    mangled_names.retain(|mangled_name| !mangled_name.is_empty() && mangled_name != name);
+
+    // Names with C linkage aren't mangled, apart from a prefix on some targets.
+    // The Itanium ABI doesn't mangle the names of variables of the global scope
+    // either, so C++ ones are recognized by their parent (`extern "C"` blocks
+    // are `LinkageSpec` entities).
+    let has_cxx_linkage = mangled_names
+        .iter()
+        .any(|mangled_name| is_cxx_mangled_name(mangled_name))
+        || (target_abi == CxxAbi::Itanium
+            && entity.get_kind() == EntityKind::VarDecl
+            && entity.get_language() == Some(Language::Cpp)
+            && matches!(
+                entity.get_semantic_parent().map(|parent| parent.get_kind()),
+                Some(EntityKind::TranslationUnit)
+            ));
+    // Operators are mangled with special codes
+    let is_identifier = name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    let other_abi_mangled_name = match enclosing_scopes(entity) {
+        Some(scopes) if has_cxx_linkage && is_identifier => match target_abi {
+            CxxAbi::Itanium => Some((CxxAbi::Msvc, msvc_mangled_name_prefix(&scopes, name))),
+            // Variables of the global scope aren't mangled
+            CxxAbi::Msvc if entity.get_kind() == EntityKind::VarDecl && scopes.is_empty() => None,
+            CxxAbi::Msvc => Some((
+                CxxAbi::Itanium,
+                itanium_mangled_name_prefix(&scopes, name, entity.is_const_method()),
+            )),
+        },
+        _ => None,
+    };
+
+    mangled_names
+        .into_iter()
+        .map(|mangled_name| (target_abi, mangled_name))
+        .chain(other_abi_mangled_name)
+        .map(|(abi, mangled_name)| LeakedDataForm::MangledName {
+            abi,
+            name: Arc::new(mangled_name),
+        })
+        .collect()
+}
+
+/// Returns true if `name` is mangled following the Itanium or the MSVC ABI
+#[cfg(feature = "extraction")]
+fn is_cxx_mangled_name(name: &str) -> bool {
+    // Symbol names are prefixed with an underscore on Apple targets
+    name.starts_with("_Z") || name.starts_with("__Z") || name.starts_with('?')
+}
+
+/// Scope enclosing a declaration
+#[cfg(feature = "extraction")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum EnclosingScope {
+    /// Named namespace, struct or class
+    Named(String),
+    /// Anonymous namespace
+    AnonymousNamespace,
+}
+
+/// Returns the RTTI names generated for a struct or class (i.e., its Itanium
+/// type info name and its MSVC type descriptor name). For class templates, the
+/// prefix shared by the RTTI names of all specializations is returned.
+#[cfg(feature = "extraction")]
+fn rtti_names(
+    entity: &Entity,
+    name: &str,
+    is_struct: bool,
+    is_template: bool,
+) -> Vec<LeakedDataForm> {
+    // Anonymous structs and classes are named after their location
+    if name.is_empty() || entity.is_anonymous() {
+        return vec![];
+    }
+
+    match enclosing_scopes(entity) {
+        Some(scopes) => vec![
+            LeakedDataForm::RttiName(Arc::new(itanium_type_info_name(&scopes, name, is_template))),
+            LeakedDataForm::RttiName(Arc::new(msvc_type_descriptor_name(
+                &scopes,
+                name,
+                is_struct,
+                is_template,
+            ))),
+        ],
+        None => vec![],
+    }
+}
+
+/// Returns the scopes enclosing a declaration, outermost first. Returns `None`
+/// for declarations that cannot be named from the global scope (e.g., local
+/// classes, or classes nested in anonymous structs).
+#[cfg(feature = "extraction")]
+fn enclosing_scopes(entity: &Entity) -> Option<Vec<EnclosingScope>> {
+    let mut scopes = vec![];
+    let mut parent = entity.get_semantic_parent();
+    while let Some(scope) = parent {
+        match scope.get_kind() {
+            EntityKind::TranslationUnit => break,
+            // `extern "C++"` blocks don't introduce a scope
+            EntityKind::LinkageSpec => {}
+            EntityKind::Namespace => scopes.push(match scope.get_name() {
+                Some(name) if !name.is_empty() && !scope.is_anonymous() => {
+                    EnclosingScope::Named(name)
+                }
+                _ => EnclosingScope::AnonymousNamespace,
+            }),
+            EntityKind::StructDecl | EntityKind::ClassDecl | EntityKind::UnionDecl => {
+                match scope.get_name() {
+                    Some(name) if !name.is_empty() => scopes.push(EnclosingScope::Named(name)),
+                    _ => return None,
+                }
+            }
+            _ => return None,
+        }
+        parent = scope.get_semantic_parent();
+    }
+    scopes.reverse();
+
+    Some(scopes)
+}
+
+/// Returns the Itanium type info name (i.e., the mangled type name stored in
+/// `_ZTS*` symbols) of a struct or class. For templates, the returned name
+/// stops before the template arguments.
+#[cfg(feature = "extraction")]
+fn itanium_type_info_name(scopes: &[EnclosingScope], name: &str, is_template: bool) -> String {
+    fn source_name(name: &str) -> String {
+        format!("{}{}", name.len(), name)
+    }
+
+    // Entities declared directly in `std` use the `St` abbreviation
+    let (is_in_std, scopes) = match scopes.split_first() {
+        Some((EnclosingScope::Named(namespace), inner_scopes)) if namespace == "std" => {
+            (true, inner_scopes)
+        }
+        _ => (false, scopes),
+    };
+    let is_nested = !scopes.is_empty();
+
+    let mut type_info_name = String::new();
+    if is_nested {
+        type_info_name.push('N');
+    }
+    if is_in_std {
+        type_info_name.push_str("St");
+    }
+    for scope in scopes {
+        type_info_name.push_str(&match scope {
+            EnclosingScope::Named(scope_name) => source_name(scope_name),
+            EnclosingScope::AnonymousNamespace => source_name("_GLOBAL__N_1"),
+        });
+    }
+    type_info_name.push_str(&source_name(name));
+    if is_template {
+        type_info_name.push('I');
+    } else if is_nested {
+        type_info_name.push('E');
+    }
+
+    type_info_name
+}
+
+/// Returns the prefix of the Itanium mangled name of a function, method or
+/// variable, which precedes the parameter types of functions (variables of the
+/// global scope aren't mangled).
+#[cfg(feature = "extraction")]
+fn itanium_mangled_name_prefix(
+    scopes: &[EnclosingScope],
+    name: &str,
+    is_const_method: bool,
+) -> String {
+    // Names are encoded like type names, with the CV-qualifiers of methods
+    // following the nested name's prefix
+    let encoding = itanium_type_info_name(scopes, name, false);
+    match encoding.strip_prefix('N') {
+        Some(nested_name) if is_const_method => format!("_ZNK{}", nested_name),
+        _ => format!("_Z{}", encoding),
+    }
+}
+
+/// Returns the MSVC type descriptor name (i.e., the decorated type name stored
+/// in `??_R0*` symbols) of a struct or class. For templates, the returned name
+/// stops before the template arguments. The hashes identifying anonymous
+/// namespaces are replaced with `GENERATED_HASH_PLACEHOLDER`.
+#[cfg(feature = "extraction")]
+fn msvc_type_descriptor_name(
+    scopes: &[EnclosingScope],
+    name: &str,
+    is_struct: bool,
+    is_template: bool,
+) -> String {
+    let prefix = if is_struct { ".?AU" } else { ".?AV" };
+    if is_template {
+        return format!("{}?${}@", prefix, name);
+    }
+
+    format!("{}{}", prefix, msvc_qualified_name(scopes, name))
+}
+
+/// Returns the prefix of the MSVC decorated name of a function, method or
+/// variable, which precedes the encoding of its type. The hashes identifying
+/// anonymous namespaces are replaced with `GENERATED_HASH_PLACEHOLDER`.
+#[cfg(feature = "extraction")]
+fn msvc_mangled_name_prefix(scopes: &[EnclosingScope], name: &str) -> String {
+    format!("?{}", msvc_qualified_name(scopes, name))
+}
+
+/// Returns the qualified name of a declaration, as encoded in MSVC decorated
+/// names (i.e., the name followed by its scopes, innermost first)
+#[cfg(feature = "extraction")]
+fn msvc_qualified_name(scopes: &[EnclosingScope], name: &str) -> String {
+    // Names are memorized in order of appearance, and names that appear again
+    // are replaced with their index in this table. Anonymous namespaces take
+    // a slot in the table, but are never referenced.
+    const MAX_BACK_REFERENCES: usize = 10;
+
+    let mut qualified_name = format!("{}@", name);
+    let mut memorized_names = vec![Some(name)];
+    // Scopes are listed innermost first
+    for scope in scopes.iter().rev() {
+        match scope {
+            EnclosingScope::Named(scope_name) => {
+                match memorized_names
+                    .iter()
+                    .position(|memorized_name| *memorized_name == Some(scope_name))
+                {
+                    Some(index) => qualified_name.push_str(&index.to_string()),
+                    None => {
+                        qualified_name.push_str(scope_name);
+                        qualified_name.push('@');
+                        memorized_names.push(Some(scope_name));
+                    }
+                }
+            }
+            EnclosingScope::AnonymousNamespace => {
+                qualified_name.push_str("?A0x");
+                qualified_name.push(GENERATED_HASH_PLACEHOLDER);
+                qualified_name.push('@');
+                memorized_names.push(None);
+            }
+        }
+        memorized_names.truncate(MAX_BACK_REFERENCES);
+    }
+    qualified_name.push('@');
+
+    qualified_name
+}
+
 impl PartialEq for PotentialLeak {
     fn eq(&self, other: &Self) -> bool {
         self.data == other.data
@@ -90,6 +514,7 @@ impl Hash for PotentialLeak {
 }
 
 /// Kind of wide chars to use when encoding wide strings
+#[cfg(feature = "extraction")]
 pub enum WideCharMode {
     /// Wide strings are encoded as UTF-16LE
     Windows,
@@ -98,6 +523,7 @@ pub enum WideCharMode {
 }
 
 /// Describes the string encoding specified for a string literal
+#[cfg(feature = "extraction")]
 enum StringLiteralEncoding {
     /// No encoding specified (i.e., typical "*" string)
     Unspecified,
@@ -113,6 +539,7 @@ enum StringLiteralEncoding {
 
 /// We have to reimplement this ourselves since the `clang` crate doesn't
 /// provide an easy way to get byte representations of `StringLiteral` entities.
+#[cfg(feature = "extraction")]
 fn string_literal_to_bytes(
     string_literal: &str,
     wide_char_mode: Option<WideCharMode>,
@@ -200,6 +627,7 @@ fn string_literal_to_bytes(
 
 /// Takes in a string literal (e.g., "str", L"str") and returns the specified
 /// encoding (extracted from the prefix) and the actual content of the string.
+#[cfg(feature = "extraction")]
 fn parse_string_literal(string_literal: &str) -> Result<(StringLiteralEncoding, &str)> {
     let mut char_it = string_literal.chars();
     let first_char = char_it.next();
@@ -254,10 +682,11 @@ fn parse_string_literal(string_literal: &str) -> Result<(StringLiteralEncoding,
     }
 }
 
-fn process_escape_sequences(string: &str) -> Option<Cow<str>> {
+#[cfg(feature = "extraction")]
+fn process_escape_sequences(string: &str) -> Option<Cow<'_, str>> {
     let mut owned: Option<String> = None;
     let mut skip_until: usize = 0;
-    for (position, char) in string.chars().enumerate() {
+    for (position, char) in string.char_indices() {
         if position < skip_until {
             continue;
         }
@@ -267,10 +696,12 @@ fn process_escape_sequences(string: &str) -> Option<Cow<str>> {
                 owned = Some(string[..position].to_owned());
             }
             let b = owned.as_mut()?;
-            let mut escape_char_it = string.chars();
-            let first_char = escape_char_it.nth(position + 1);
+            // Note: '\\' is a single byte long, so the escape sequence starts
+            // right after it
+            let mut escape_char_it = string[position + 1..].chars();
+            let first_char = escape_char_it.next();
             if let Some(first_char) = first_char {
-                skip_until = position + 2;
+                skip_until = position + 1 + first_char.len_utf8();
                 match first_char {
                     // Simple escape sequences
                     'a' => b.push('\x07'),
@@ -319,7 +750,7 @@ fn process_escape_sequences(string: &str) -> Option<Cow<str>> {
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "extraction"))]
 mod tests {
     use super::*;
 
@@ -414,6 +845,16 @@ mod tests {
         assert!(process_escape_sequences(r"invalid\").is_none());
     }
 
+    #[test]
+    fn process_escape_sequences_non_ascii_chars() {
+        // Escape sequences following multi-byte chars must be located with
+        // byte offsets
+        assert_eq!(
+            process_escape_sequences(r"é\n|😂\101|\é").expect("Failed to escape string"),
+            "é\n|😂A|é"
+        );
+    }
+
     #[test]
     fn process_escape_sequences_char_escape_sequences() {
         assert_eq!(
@@ -429,4 +870,128 @@ mod tests {
             "\x00\x01\x02\x03\x04\x05\x06\x07\x08\x40"
         );
     }
+
+    #[test]
+    fn itanium_type_info_names() {
+        let named = |name: &str| EnclosingScope::Named(name.to_string());
+
+        assert_eq!(itanium_type_info_name(&[], "MyClass", false), "7MyClass");
+        assert_eq!(
+            itanium_type_info_name(&[named("foo"), named("Outer")], "MyClass", false),
+            "N3foo5Outer7MyClassE"
+        );
+        assert_eq!(
+            itanium_type_info_name(&[named("std")], "MyClass", false),
+            "St7MyClass"
+        );
+        assert_eq!(
+            itanium_type_info_name(&[named("std"), named("foo")], "MyClass", false),
+            "NSt3foo7MyClassE"
+        );
+        assert_eq!(
+            itanium_type_info_name(&[EnclosingScope::AnonymousNamespace], "MyClass", false),
+            "N12_GLOBAL__N_17MyClassE"
+        );
+        assert_eq!(
+            itanium_type_info_name(&[], "MyTemplate", true),
+            "10MyTemplateI"
+        );
+        assert_eq!(
+            itanium_type_info_name(&[named("foo")], "MyTemplate", true),
+            "N3foo10MyTemplateI"
+        );
+    }
+
+    #[test]
+    fn mangled_name_prefixes() {
+        let named = |name: &str| EnclosingScope::Named(name.to_string());
+
+        // `void foo()`
+        let itanium_name = itanium_mangled_name_prefix(&[], "foo", false);
+        assert_eq!(itanium_name, "_Z3foo");
+        assert!("_Z3foov".starts_with(&itanium_name));
+        let msvc_name = msvc_mangled_name_prefix(&[], "foo");
+        assert_eq!(msvc_name, "?foo@@");
+        assert!("?foo@@YAXXZ".starts_with(&msvc_name));
+        // `int MyClass::my_method(int) const`
+        let itanium_name = itanium_mangled_name_prefix(&[named("MyClass")], "my_method", true);
+        assert_eq!(itanium_name, "_ZNK7MyClass9my_methodE");
+        assert!("_ZNK7MyClass9my_methodEi".starts_with(&itanium_name));
+        let msvc_name = msvc_mangled_name_prefix(&[named("MyClass")], "my_method");
+        assert_eq!(msvc_name, "?my_method@MyClass@@");
+        assert!("?my_method@MyClass@@QEBAHH@Z".starts_with(&msvc_name));
+        // `int my_namespace::my_namespaced_variable`
+        assert_eq!(
+            itanium_mangled_name_prefix(&[named("my_namespace")], "my_namespaced_variable", false),
+            "_ZN12my_namespace22my_namespaced_variableE"
+        );
+        assert_eq!(
+            msvc_mangled_name_prefix(&[named("my_namespace")], "my_namespaced_variable"),
+            "?my_namespaced_variable@my_namespace@@"
+        );
+        // Functions of anonymous namespaces
+        assert_eq!(
+            itanium_mangled_name_prefix(&[EnclosingScope::AnonymousNamespace], "foo", false),
+            "_ZN12_GLOBAL__N_13fooE"
+        );
+        assert_eq!(
+            msvc_mangled_name_prefix(&[EnclosingScope::AnonymousNamespace], "foo"),
+            "?foo@?A0x*@@"
+        );
+    }
+
+    #[test]
+    fn msvc_type_descriptor_names() {
+        let named = |name: &str| EnclosingScope::Named(name.to_string());
+
+        assert_eq!(
+            msvc_type_descriptor_name(&[], "MyStruct", true, false),
+            ".?AUMyStruct@@"
+        );
+        assert_eq!(
+            msvc_type_descriptor_name(&[named("foo"), named("Outer")], "MyClass", false, false),
+            ".?AVMyClass@Outer@foo@@"
+        );
+        assert_eq!(
+            msvc_type_descriptor_name(
+                &[named("foo"), EnclosingScope::AnonymousNamespace],
+                "MyClass",
+                false,
+                false
+            ),
+            ".?AVMyClass@?A0x*@foo@@"
+        );
+        // Names that appear again are back-references
+        assert_eq!(
+            msvc_type_descriptor_name(&[named("foo"), named("bar")], "foo", false, false),
+            ".?AVfoo@bar@0@"
+        );
+        assert_eq!(
+            msvc_type_descriptor_name(
+                &[
+                    named("foo"),
+                    EnclosingScope::AnonymousNamespace,
+                    named("foo")
+                ],
+                "MyClass",
+                true,
+                false
+            ),
+            ".?AUMyClass@foo@?A0x*@1@"
+        );
+        // Only the first 10 names are memorized (scopes listed innermost first)
+        let scopes: Vec<_> = ["a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "j", "a"]
+            .iter()
+            .rev()
+            .map(|name| named(name))
+            .collect();
+        assert_eq!(
+            msvc_type_descriptor_name(&scopes, "MyClass", false, false),
+            ".?AVMyClass@a@b@c@d@e@f@g@h@i@j@j@1@"
+        );
+        assert_eq!(
+            msvc_type_descriptor_name(&[named("foo")], "MyTemplate", false, true),
+            ".?AV?$MyTemplate@"
+        );
+    }
 }
@@ -0,0 +1,1070 @@
+use std::{
+    cmp::Reverse,
+    collections::{BTreeSet, HashMap},
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{anyhow, Context, Result};
+use rayon::prelude::*;
+
+use crate::{
+    binary_format::{BinaryFileData, BinaryFileLayout, SectionFilter},
+    information_leak::{
+        BinaryLocation, ConfirmedLeak, LeakLocation, LeakedDataForm, PotentialLeak,
+        GENERATED_HASH_PLACEHOLDER,
+    },
+    pattern_matching::{MatchingEngine, PatternMatch, PatternMatcher},
+};
+
+/// Processes glob expressions and returns the list of binary files to scan
+pub fn gather_binary_file_paths(binary_file_path_globs: &[String]) -> Result<BTreeSet<PathBuf>> {
+    let mut binary_file_paths = BTreeSet::new();
+    for glob_expression in binary_file_path_globs {
+        let paths = glob::glob(glob_expression)
+            .with_context(|| format!("'{}' is not a valid glob expression", glob_expression))?
+            .collect::<Result<Vec<_>, _>>()?;
+        if paths.is_empty() {
+            return Err(anyhow!("'{}' is not a valid file path.", glob_expression));
+        }
+
+        for path in paths {
+            if !path.is_file() {
+                return Err(anyhow!("'{}' is not a valid file path.", path.display()));
+            }
+            binary_file_paths.insert(path.canonicalize()?);
+        }
+    }
+
+    Ok(binary_file_paths)
+}
+
+/// Looks for leaks in multiple binary files in parallel. Leaks are
+/// deduplicated separately for each binary file, and returned grouped by
+/// binary file.
+pub fn find_leaks_in_binary_files<SortedConfirmedLeak>(
+    binary_file_paths: &BTreeSet<PathBuf>,
+    leak_desc: &[PotentialLeak],
+    matching_engine: MatchingEngine,
+    section_filter: &SectionFilter,
+) -> Result<Vec<SortedConfirmedLeak>>
+where
+    SortedConfirmedLeak: From<ConfirmedLeak> + Ord + Eq + Send,
+{
+    let leak_matcher = build_leak_matcher(leak_desc, matching_engine)?;
+    let leaks_per_binary = binary_file_paths
+        .par_iter()
+        .map(|binary_file_path| {
+            log::info!("Looking for leaks in '{}'...", binary_file_path.display());
+            find_leaks_in_binary_file(binary_file_path, leak_desc, &leak_matcher, section_filter)
+        })
+        .collect::<Result<Vec<BTreeSet<SortedConfirmedLeak>>>>()?;
+
+    Ok(leaks_per_binary.into_iter().flatten().collect())
+}
+
+/// Maximum number of hexadecimal digits matched by `GENERATED_HASH_PLACEHOLDER`
+const MAX_GENERATED_HASH_SIZE: usize = 8;
+
+/// Pattern matcher looking for every form of a set of potential leaks
+pub struct LeakMatcher {
+    matcher: PatternMatcher,
+    /// Potential leak and form corresponding to each pattern
+    pattern_origins: Vec<PatternOrigin>,
+}
+
+struct PatternOrigin {
+    /// Index of the potential leak in `leak_desc`
+    leak_index: usize,
+    form: LeakedDataForm,
+    /// Size of the pattern in bytes
+    size: u64,
+    /// Parts of the form that follow the pattern, each of them preceded by a
+    /// generated hash (i.e., the pattern stops at the first
+    /// `GENERATED_HASH_PLACEHOLDER` of the form)
+    parts_after_hashes: Vec<Vec<u8>>,
+}
+
+/// Complete match of one of the forms of a potential leak
+struct LeakMatch {
+    /// Index of the matched pattern in `LeakMatcher::pattern_origins`
+    pattern_index: usize,
+    range: Range<u64>,
+}
+
+impl LeakMatcher {
+    /// Checks that the parts of the form that follow the pattern matched by
+    /// `pattern_match` (and the generated hashes that precede them) are
+    /// present in `data` before `end`, and returns the range covered by the
+    /// complete form.
+    fn complete_match(
+        &self,
+        data: &[u8],
+        end: usize,
+        pattern_match: &PatternMatch,
+    ) -> Option<LeakMatch> {
+        let pattern_origin = &self.pattern_origins[pattern_match.pattern_index];
+        let mut match_end = pattern_match.offset + pattern_origin.size as usize;
+        for part in &pattern_origin.parts_after_hashes {
+            let hash_size = data[match_end..end]
+                .iter()
+                .take(MAX_GENERATED_HASH_SIZE)
+                .take_while(|byte| byte.is_ascii_hexdigit())
+                .count();
+            if hash_size == 0 || !data[match_end + hash_size..end].starts_with(part) {
+                return None;
+            }
+            match_end += hash_size + part.len();
+        }
+
+        Some(LeakMatch {
+            pattern_index: pattern_match.pattern_index,
+            range: pattern_match.offset as u64..match_end as u64,
+        })
+    }
+
+    /// Removes the matches located inside a larger match of the same leak
+    /// (e.g., a function's name found as part of its mangled name), so that
+    /// each occurrence is only reported once, in its most specific form.
+    fn remove_nested_matches(&self, matches: Vec<LeakMatch>) -> Vec<LeakMatch> {
+        // Distinct ranges matched for each leak, sorted by start offset, then
+        // by decreasing end offset, along with the largest end offset of the
+        // ranges that precede them
+        let mut ranges_per_leak: HashMap<usize, Vec<Range<u64>>> = HashMap::new();
+        for leak_match in &matches {
+            ranges_per_leak
+                .entry(self.pattern_origins[leak_match.pattern_index].leak_index)
+                .or_default()
+                .push(leak_match.range.clone());
+        }
+        let ranges_per_leak: HashMap<usize, (Vec<Range<u64>>, Vec<u64>)> = ranges_per_leak
+            .into_iter()
+            .map(|(leak_index, mut ranges)| {
+                ranges.sort_by_key(|range| (range.start, Reverse(range.end)));
+                ranges.dedup();
+                let mut max_end = 0;
+                let preceding_max_ends = ranges
+                    .iter()
+                    .map(|range| {
+                        let preceding_max_end = max_end;
+                        max_end = max_end.max(range.end);
+                        preceding_max_end
+                    })
+                    .collect();
+                (leak_index, (ranges, preceding_max_ends))
+            })
+            .collect();
+
+        matches
+            .into_iter()
+            .filter(|leak_match| {
+                let (ranges, preceding_max_ends) =
+                    &ranges_per_leak[&self.pattern_origins[leak_match.pattern_index].leak_index];
+                let range = &leak_match.range;
+                // Ranges that precede this one start before it (or at the same
+                // offset, but end after it), the match is nested if one of
+                // them ends after it
+                let position = ranges
+                    .binary_search_by_key(&(range.start, Reverse(range.end)), |other| {
+                        (other.start, Reverse(other.end))
+                    })
+                    .expect("Match ranges should be indexed");
+                preceding_max_ends[position] < range.end
+            })
+            .collect()
+    }
+}
+
+/// Builds a matcher for the bytes of the given leaks, as well as for their
+/// alternative forms.
+pub fn build_leak_matcher(
+    leak_desc: &[PotentialLeak],
+    matching_engine: MatchingEngine,
+) -> Result<LeakMatcher> {
+    let mut patterns: Vec<&[u8]> = Vec::with_capacity(leak_desc.len());
+    let mut pattern_origins = Vec::with_capacity(leak_desc.len());
+    for (leak_index, leak) in leak_desc.iter().enumerate() {
+        patterns.push(&leak.bytes);
+        pattern_origins.push(PatternOrigin {
+            leak_index,
+            form: LeakedDataForm::Plain,
+            size: leak.bytes.len() as u64,
+            parts_after_hashes: vec![],
+        });
+        for form in &leak.alternative_forms {
+            if let Some(representation) = form.representation() {
+                // Generated hashes are checked once the pattern is found
+                let mut parts = representation.split(GENERATED_HASH_PLACEHOLDER);
+                let pattern = parts.next().unwrap_or_default();
+                patterns.push(pattern.as_bytes());
+                pattern_origins.push(PatternOrigin {
+                    leak_index,
+                    form: form.clone(),
+                    size: pattern.len() as u64,
+                    parts_after_hashes: parts.map(|part| part.as_bytes().to_vec()).collect(),
+                });
+            }
+        }
+    }
+
+    Ok(LeakMatcher {
+        matcher: PatternMatcher::new(matching_engine, &patterns)
+            .with_context(|| "Failed to build the pattern matcher")?,
+        pattern_origins,
+    })
+}
+
+/// Looks for leaks in a binary file. `leak_matcher` must have been built from
+/// `leak_desc` with `build_leak_matcher`.
+pub fn find_leaks_in_binary_file<SortedConfirmedLeak>(
+    binary_file_path: &Path,
+    leak_desc: &[PotentialLeak],
+    leak_matcher: &LeakMatcher,
+    section_filter: &SectionFilter,
+) -> Result<BTreeSet<SortedConfirmedLeak>>
+where
+    SortedConfirmedLeak: From<ConfirmedLeak> + Ord + Eq + Send,
+{
+    // Map binary file's content in memory
+    let bin_data = BinaryFileData::open(binary_file_path)?;
+    // Parse the binary file's headers to be able to map offsets to sections.
+    // Files with truncated or corrupted headers are still scanned as raw data.
+    let bin_layout = BinaryFileLayout::parse(&bin_data).unwrap_or_else(|err| {
+        log::warn!(
+            "Failed to parse binary file '{}', section information won't be available: {}",
+            binary_file_path.display(),
+            err
+        );
+        BinaryFileLayout::default()
+    });
+
+    // Look for leaks in each range. Architecture slices of universal binaries
+    // are scanned separately, so that leaks cannot span over two slices.
+    // Sections excluded by the user are skipped.
+    let shared_binary_file_path = Arc::new(binary_file_path.to_path_buf().canonicalize()?);
+    let leak_matches = bin_layout
+        .scan_ranges(bin_data.len() as u64, section_filter)
+        .into_iter()
+        .flat_map(|range| {
+            let range = range.start as usize..range.end as usize;
+            let bin_data = &bin_data;
+            leak_matcher
+                .matcher
+                .find_matches(bin_data, range.clone())
+                .into_iter()
+                .filter_map(move |pattern_match| {
+                    leak_matcher.complete_match(bin_data, range.end, &pattern_match)
+                })
+        })
+        .collect::<Vec<_>>();
+    let confirmed_leaks = leak_matcher
+        .remove_nested_matches(leak_matches)
+        .into_par_iter()
+        // Bytes match, the leaks are confirmed
+        .map(|leak_match| {
+            let pattern_origin = &leak_matcher.pattern_origins[leak_match.pattern_index];
+            let leak = &leak_desc[pattern_origin.leak_index];
+            let range = leak_match.range.start as usize..leak_match.range.end as usize;
+            // Report the generated hashes found in the binary file
+            let form = if pattern_origin.parts_after_hashes.is_empty() {
+                pattern_origin.form.clone()
+            } else {
+                pattern_origin
+                    .form
+                    .with_representation(String::from_utf8_lossy(&bin_data[range.clone()]).into())
+            };
+            SortedConfirmedLeak::from(ConfirmedLeak {
+                data_type: leak.data_type,
+                data: leak.data.clone(),
+                location: LeakLocation {
+                    source: leak.declaration_metadata.clone(),
+                    binary: locate_in_binary_file(
+                        shared_binary_file_path.clone(),
+                        leak_match.range.start,
+                        range.len() as u64,
+                        form,
+                        &bin_layout,
+                    ),
+                },
+            })
+        })
+        .collect();
+
+    Ok(confirmed_leaks)
+}
+
+/// Builds a `BinaryLocation` for the data found at the given offset, using the
+/// binary file's layout to determine where the data is located.
+fn locate_in_binary_file(
+    file: Arc<PathBuf>,
+    offset: u64,
+    size: u64,
+    form: LeakedDataForm,
+    layout: &BinaryFileLayout,
+) -> BinaryLocation {
+    let location = layout.locate(offset);
+    BinaryLocation {
+        file,
+        offset,
+        size,
+        form,
+        architecture: location.architecture,
+        archive_member: location.archive_member,
+        section: location.section,
+        segment: location.segment,
+        virtual_address: location.virtual_address,
+        relative_virtual_address: location.relative_virtual_address,
+        data_directory: location.data_directory,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    #[cfg(feature = "extraction")]
+    use crate::compilation_database::{CompilationDatabase, FileListDatabase};
+    #[cfg(feature = "extraction")]
+    use crate::extraction::{extract_artifacts_from_source_files, ExtractionOptions};
+    use crate::information_leak::{
+        ConfirmedLeakWithUniqueLocation, ConfirmedLeakWithUniqueValue, CxxAbi, LeakedDataType,
+        SourceLocation,
+    };
+
+    use super::*;
+
+    #[cfg(feature = "extraction")]
+    use serial_test::serial;
+
+    const FILE_LIST_PROJ_PATH: &str = "tests/data/main/file_list_proj";
+
+    /// Creates a `PotentialLeak` for an ASCII string literal
+    fn ascii_string_leak(data: &str) -> PotentialLeak {
+        PotentialLeak {
+            data_type: LeakedDataType::StringLiteral,
+            data: Arc::new(data.to_string()),
+            bytes: data.as_bytes().to_vec(),
+            declaration_metadata: Arc::new(SourceLocation {
+                file: PathBuf::from("main.cc"),
+                line: 1,
+                columns: None,
+            }),
+            alternative_forms: vec![],
+        }
+    }
+
+    /// Builds the default matcher for the given leaks
+    fn leak_matcher(potential_leaks: &[PotentialLeak]) -> LeakMatcher {
+        build_leak_matcher(potential_leaks, MatchingEngine::default())
+            .expect("build_leak_matcher failed")
+    }
+
+    #[test]
+    fn find_leaks_in_corrupted_binary_file() {
+        let potential_leaks = vec![ascii_string_leak("included_string_literal")];
+
+        // Truncate an ELF file, its section headers are located at the end
+        let elf_data = std::fs::read(
+            PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join(FILE_LIST_PROJ_PATH)
+                .join("a.out"),
+        )
+        .expect("Failed to read ELF file");
+        let truncated_elf_data = &elf_data[..0x2100];
+        assert!(BinaryFileLayout::parse(truncated_elf_data).is_err());
+        let mut bin_file = tempfile::NamedTempFile::new().unwrap();
+        bin_file.write_all(truncated_elf_data).unwrap();
+
+        // The file is scanned as raw data
+        let confirmed_leaks: BTreeSet<ConfirmedLeakWithUniqueLocation> = find_leaks_in_binary_file(
+            bin_file.path(),
+            &potential_leaks,
+            &leak_matcher(&potential_leaks),
+            &SectionFilter::default(),
+        )
+        .expect("find_leaks_in_binary_file failed");
+        assert_eq!(confirmed_leaks.len(), 1);
+        let binary_location = &confirmed_leaks.iter().next().unwrap().location.binary;
+        assert_eq!(binary_location.offset, 0x2020);
+        assert_eq!(binary_location.section, None);
+    }
+
+    #[test]
+    fn find_leaks_in_elf_sections() {
+        let potential_leaks = vec![ascii_string_leak("included_string_literal")];
+
+        // Look for leaks present in the compiled binary
+        let bin_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join(FILE_LIST_PROJ_PATH)
+            .join("a.out");
+
+        let confirmed_leaks: BTreeSet<ConfirmedLeakWithUniqueLocation> = find_leaks_in_binary_file(
+            &bin_path,
+            &potential_leaks,
+            &leak_matcher(&potential_leaks),
+            &SectionFilter::default(),
+        )
+        .expect("find_leaks_in_binary_file failed");
+        let binary_locations: Vec<_> = confirmed_leaks
+            .iter()
+            .map(|leak| &leak.location.binary)
+            .collect();
+
+        assert_eq!(binary_locations.len(), 2);
+        // Loaded string
+        assert_eq!(binary_locations[0].offset, 0x2020);
+        assert_eq!(
+            binary_locations[0].section.as_deref().map(String::as_str),
+            Some(".rodata")
+        );
+        assert_eq!(binary_locations[0].virtual_address, Some(0x2020));
+        // Symbol name
+        assert_eq!(binary_locations[1].offset, 0x39e5);
+        assert_eq!(
+            binary_locations[1].section.as_deref().map(String::as_str),
+            Some(".strtab")
+        );
+        assert_eq!(binary_locations[1].segment, None);
+        assert_eq!(binary_locations[1].virtual_address, None);
+    }
+
+    #[test]
+    fn find_leaks_in_elf_excluded_sections() {
+        let potential_leaks = vec![ascii_string_leak("included_string_literal")];
+        let section_filter =
+            SectionFilter::new(&[], &[".strtab".to_string()]).expect("SectionFilter::new failed");
+
+        let bin_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join(FILE_LIST_PROJ_PATH)
+            .join("a.out");
+        let confirmed_leaks: BTreeSet<ConfirmedLeakWithUniqueLocation> = find_leaks_in_binary_file(
+            &bin_path,
+            &potential_leaks,
+            &leak_matcher(&potential_leaks),
+            &section_filter,
+        )
+        .expect("find_leaks_in_binary_file failed");
+
+        // Only the string from ".rodata" remains
+        assert_eq!(confirmed_leaks.len(), 1);
+        assert_eq!(
+            confirmed_leaks
+                .iter()
+                .next()
+                .and_then(|leak| leak.location.binary.section.as_deref())
+                .map(String::as_str),
+            Some(".rodata")
+        );
+    }
+
+    #[test]
+    fn find_leaks_in_elf_declaration_names() {
+        let mut method_leak = ascii_string_leak("my_method");
+        method_leak.data_type = LeakedDataType::FunctionName;
+        method_leak.alternative_forms = vec![LeakedDataForm::MangledName {
+            abi: CxxAbi::Itanium,
+            name: Arc::new("_ZNK7MyClass9my_methodEi".to_string()),
+        }];
+        let mut enum_leak = ascii_string_leak("MyEnum");
+        enum_leak.data_type = LeakedDataType::EnumName;
+        let mut enum_class_leak = ascii_string_leak("MyEnumClass");
+        enum_class_leak.data_type = LeakedDataType::EnumName;
+        let mut namespace_leak = ascii_string_leak("my_namespace");
+        namespace_leak.data_type = LeakedDataType::NamespaceName;
+        let variable_leak = |name: &str, mangled_name: &str| {
+            let mut variable_leak = ascii_string_leak(name);
+            variable_leak.data_type = LeakedDataType::VariableName;
+            variable_leak.alternative_forms = vec![LeakedDataForm::MangledName {
+                abi: CxxAbi::Itanium,
+                name: Arc::new(mangled_name.to_string()),
+            }];
+            variable_leak
+        };
+        let potential_leaks = vec![
+            method_leak,
+            enum_leak,
+            enum_class_leak,
+            namespace_leak,
+            variable_leak("my_static_member", "_ZN7MyClass16my_static_memberE"),
+            variable_leak(
+                "my_namespaced_variable",
+                "_ZN12my_namespace22my_namespaced_variableE",
+            ),
+            variable_leak(
+                "anonymous_namespace_variable",
+                "_ZN12_GLOBAL__N_128anonymous_namespace_variableE",
+            ),
+            variable_leak("my_static_local", "_ZZ4mainE15my_static_local"),
+        ];
+
+        let bin_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join(FILE_LIST_PROJ_PATH)
+            .join("a.out");
+        let confirmed_leaks: BTreeSet<ConfirmedLeakWithUniqueLocation> = find_leaks_in_binary_file(
+            &bin_path,
+            &potential_leaks,
+            &leak_matcher(&potential_leaks),
+            &SectionFilter::default(),
+        )
+        .expect("find_leaks_in_binary_file failed");
+        let leaks: BTreeSet<_> = confirmed_leaks
+            .iter()
+            .map(|leak| (leak.data.as_str(), leak.location.binary.form.clone()))
+            .collect();
+
+        // Names declared in the test project are found in symbol names, or in
+        // type info names (enum constant names are only found in debug info)
+        assert_eq!(
+            leaks,
+            BTreeSet::from([
+                (
+                    "my_method",
+                    LeakedDataForm::MangledName {
+                        abi: CxxAbi::Itanium,
+                        name: Arc::new("_ZNK7MyClass9my_methodEi".to_string())
+                    }
+                ),
+                ("MyEnum", LeakedDataForm::Plain),
+                ("MyEnumClass", LeakedDataForm::Plain),
+                ("my_namespace", LeakedDataForm::Plain),
+                (
+                    "my_static_member",
+                    LeakedDataForm::MangledName {
+                        abi: CxxAbi::Itanium,
+                        name: Arc::new("_ZN7MyClass16my_static_memberE".to_string())
+                    }
+                ),
+                (
+                    "my_namespaced_variable",
+                    LeakedDataForm::MangledName {
+                        abi: CxxAbi::Itanium,
+                        name: Arc::new("_ZN12my_namespace22my_namespaced_variableE".to_string())
+                    }
+                ),
+                (
+                    "anonymous_namespace_variable",
+                    LeakedDataForm::MangledName {
+                        abi: CxxAbi::Itanium,
+                        name: Arc::new(
+                            "_ZN12_GLOBAL__N_128anonymous_namespace_variableE".to_string()
+                        )
+                    }
+                ),
+                (
+                    "my_static_local",
+                    LeakedDataForm::MangledName {
+                        abi: CxxAbi::Itanium,
+                        name: Arc::new("_ZZ4mainE15my_static_local".to_string())
+                    }
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn find_leaks_in_universal_binary_slices() {
+        let potential_leaks = vec![
+            ascii_string_leak("shared_string"),
+            ascii_string_leak("arm64_only_string"),
+        ];
+
+        let bin_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/data/binary_format/universal.macho");
+        let confirmed_leaks: BTreeSet<ConfirmedLeakWithUniqueLocation> = find_leaks_in_binary_file(
+            &bin_path,
+            &potential_leaks,
+            &leak_matcher(&potential_leaks),
+            &SectionFilter::default(),
+        )
+        .expect("find_leaks_in_binary_file failed");
+        let leaks: Vec<_> = confirmed_leaks
+            .iter()
+            .map(|leak| {
+                (
+                    leak.data.as_str(),
+                    leak.location
+                        .binary
+                        .architecture
+                        .as_deref()
+                        .map(String::as_str),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            leaks,
+            vec![
+                ("shared_string", Some("x86_64")),
+                ("shared_string", Some("arm64")),
+                ("arm64_only_string", Some("arm64")),
+            ]
+        );
+    }
+
+    #[test]
+    fn find_leaks_in_archive_members() {
+        let potential_leaks = vec![
+            ascii_string_leak("foo_secret_string"),
+            ascii_string_leak("bar_secret_string"),
+        ];
+
+        let bin_path =
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data/binary_format/libmixed.a");
+        let confirmed_leaks: BTreeSet<ConfirmedLeakWithUniqueLocation> = find_leaks_in_binary_file(
+            &bin_path,
+            &potential_leaks,
+            &leak_matcher(&potential_leaks),
+            &SectionFilter::default(),
+        )
+        .expect("find_leaks_in_binary_file failed");
+        let leaks: Vec<_> = confirmed_leaks
+            .iter()
+            .map(|leak| {
+                (
+                    leak.data.as_str(),
+                    leak.location
+                        .binary
+                        .archive_member
+                        .as_deref()
+                        .map(String::as_str),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            leaks,
+            vec![
+                ("foo_secret_string", Some("foo.o")),
+                ("bar_secret_string", Some("bar.obj")),
+            ]
+        );
+    }
+
+    #[test]
+    fn find_leaks_in_archive_symbol_table() {
+        let potential_leaks = vec![
+            ascii_string_leak("exported_secret_function"),
+            ascii_string_leak("long_member_name"),
+        ];
+
+        let bin_path =
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data/binary_format/libsymbols.a");
+        let confirmed_leaks: BTreeSet<ConfirmedLeakWithUniqueLocation> = find_leaks_in_binary_file(
+            &bin_path,
+            &potential_leaks,
+            &leak_matcher(&potential_leaks),
+            &SectionFilter::default(),
+        )
+        .expect("find_leaks_in_binary_file failed");
+        let leaks: Vec<_> = confirmed_leaks
+            .iter()
+            .map(|leak| {
+                (
+                    leak.data.as_str(),
+                    leak.location.binary.offset,
+                    leak.location
+                        .binary
+                        .archive_member
+                        .as_deref()
+                        .map(String::as_str),
+                )
+            })
+            .collect();
+
+        // Data found in the archive's symbol table and long names table isn't
+        // part of any member
+        assert_eq!(
+            leaks,
+            vec![
+                ("exported_secret_function", 0x4c, None),
+                ("long_member_name", 0xa2, None),
+                (
+                    "exported_secret_function",
+                    0x19b,
+                    Some("long_member_name_object.o")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn find_leaks_in_alternative_forms() {
+        let mut function_leak = ascii_string_leak("secret_function");
+        function_leak.data_type = LeakedDataType::FunctionName;
+        function_leak.alternative_forms = vec![LeakedDataForm::MangledName {
+            abi: CxxAbi::Itanium,
+            name: Arc::new("bar_secret_string".to_string()),
+        }];
+        let potential_leaks = vec![function_leak];
+
+        let bin_path =
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data/binary_format/libmixed.a");
+        let confirmed_leaks: BTreeSet<ConfirmedLeakWithUniqueLocation> = find_leaks_in_binary_file(
+            &bin_path,
+            &potential_leaks,
+            &leak_matcher(&potential_leaks),
+            &SectionFilter::default(),
+        )
+        .expect("find_leaks_in_binary_file failed");
+
+        // Leaks are reported with the value from the source code, along with
+        // the form that matched
+        assert_eq!(confirmed_leaks.len(), 1);
+        let leak = confirmed_leaks.iter().next().unwrap();
+        assert_eq!(*leak.data, "secret_function");
+        assert_eq!(
+            leak.location.binary.form,
+            LeakedDataForm::MangledName {
+                abi: CxxAbi::Itanium,
+                name: Arc::new("bar_secret_string".to_string())
+            }
+        );
+        assert_eq!(leak.location.binary.size, 17);
+    }
+
+    #[test]
+    fn find_leaks_in_most_specific_form() {
+        let mut function_leak = ascii_string_leak("secret_string");
+        function_leak.data_type = LeakedDataType::FunctionName;
+        function_leak.alternative_forms = vec![
+            LeakedDataForm::MangledName {
+                abi: CxxAbi::Itanium,
+                name: Arc::new("bar_secret_string".to_string()),
+            },
+            LeakedDataForm::MangledName {
+                abi: CxxAbi::Itanium,
+                name: Arc::new("r_secret_str".to_string()),
+            },
+        ];
+        let potential_leaks = vec![function_leak];
+
+        let bin_path =
+            PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/data/binary_format/libmixed.a");
+        let confirmed_leaks: BTreeSet<ConfirmedLeakWithUniqueLocation> = find_leaks_in_binary_file(
+            &bin_path,
+            &potential_leaks,
+            &leak_matcher(&potential_leaks),
+            &SectionFilter::default(),
+        )
+        .expect("find_leaks_in_binary_file failed");
+        let leaks: Vec<_> = confirmed_leaks
+            .iter()
+            .map(|leak| {
+                (
+                    leak.location.binary.offset,
+                    leak.location.binary.form.clone(),
+                )
+            })
+            .collect();
+
+        // Matches located inside a larger match of the same leak aren't
+        // reported
+        assert_eq!(
+            leaks,
+            vec![
+                (0x90, LeakedDataForm::Plain),
+                (
+                    0x40c,
+                    LeakedDataForm::MangledName {
+                        abi: CxxAbi::Itanium,
+                        name: Arc::new("bar_secret_string".to_string())
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn find_leaks_with_generated_hashes() {
+        let mut class_leak = ascii_string_leak("MyClass");
+        class_leak.data_type = LeakedDataType::ClassName;
+        class_leak.alternative_forms = vec![LeakedDataForm::RttiName(Arc::new(
+            ".?AVMyClass@?A0x*@foo@@".to_string(),
+        ))];
+        let potential_leaks = vec![class_leak];
+
+        let mut bin_file = tempfile::NamedTempFile::new().unwrap();
+        bin_file
+            .write_all(
+                b"\0.?AVMyClass@?A0x1a2b3c4d@foo@@\0.?AVMyClass@?A0x1a2b3c4d@bar@@\0.?AVMyClass@?A0x@foo@@\0",
+            )
+            .unwrap();
+        let confirmed_leaks: BTreeSet<ConfirmedLeakWithUniqueLocation> = find_leaks_in_binary_file(
+            bin_file.path(),
+            &potential_leaks,
+            &leak_matcher(&potential_leaks),
+            &SectionFilter::default(),
+        )
+        .expect("find_leaks_in_binary_file failed");
+        let leaks: Vec<_> = confirmed_leaks
+            .iter()
+            .map(|leak| {
+                (
+                    leak.location.binary.offset,
+                    leak.location.binary.size,
+                    leak.location.binary.form.clone(),
+                )
+            })
+            .collect();
+
+        // The hash is matched, and reported along with the rest of the name.
+        // Names with other enclosing scopes, or without a hash, don't match.
+        assert_eq!(
+            leaks,
+            vec![
+                (
+                    0x1,
+                    30,
+                    LeakedDataForm::RttiName(Arc::new(
+                        ".?AVMyClass@?A0x1a2b3c4d@foo@@".to_string()
+                    ))
+                ),
+                (0x24, 7, LeakedDataForm::Plain),
+                (0x43, 7, LeakedDataForm::Plain),
+            ]
+        );
+    }
+
+    #[test]
+    fn gather_binary_file_paths_globs() {
+        let root_dir_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        let binary_file_paths = gather_binary_file_paths(&[
+            format!(
+                "{}/tests/data/binary_format/*.macho",
+                root_dir_path.display()
+            ),
+            format!("{}/{}/a.out", root_dir_path.display(), FILE_LIST_PROJ_PATH),
+        ])
+        .expect("gather_binary_file_paths failed");
+
+        assert_eq!(
+            binary_file_paths.into_iter().collect::<Vec<_>>(),
+            vec![
+                root_dir_path
+                    .join("tests/data/binary_format/universal.macho")
+                    .canonicalize()
+                    .unwrap(),
+                root_dir_path
+                    .join(FILE_LIST_PROJ_PATH)
+                    .join("a.out")
+                    .canonicalize()
+                    .unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn gather_binary_file_paths_invalid_path() {
+        assert!(gather_binary_file_paths(&["does/not/exist".to_string()]).is_err());
+    }
+
+    #[test]
+    fn find_leaks_in_multiple_binary_files() {
+        let potential_leaks = vec![
+            ascii_string_leak("included_string_literal"),
+            ascii_string_leak("shared_string"),
+        ];
+        let root_dir_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        let elf_path = root_dir_path
+            .join(FILE_LIST_PROJ_PATH)
+            .join("a.out")
+            .canonicalize()
+            .unwrap();
+        let macho_path = root_dir_path
+            .join("tests/data/binary_format/universal.macho")
+            .canonicalize()
+            .unwrap();
+
+        let confirmed_leaks: Vec<ConfirmedLeakWithUniqueValue> = find_leaks_in_binary_files(
+            &BTreeSet::from_iter([elf_path.clone(), macho_path.clone()]),
+            &potential_leaks,
+            MatchingEngine::default(),
+            &SectionFilter::default(),
+        )
+        .expect("find_leaks_in_binary_files failed");
+        let leaks: Vec<_> = confirmed_leaks
+            .iter()
+            .map(|leak| (leak.data.as_str(), leak.location.binary.file.as_path()))
+            .collect();
+
+        // Leaks are deduplicated and grouped per binary file
+        let mut expected_leaks = vec![
+            ("included_string_literal", elf_path.as_path()),
+            ("shared_string", macho_path.as_path()),
+        ];
+        expected_leaks.sort_by_key(|(_, path)| *path);
+        assert_eq!(leaks, expected_leaks);
+    }
+
+    #[cfg(windows)]
+    #[cfg(feature = "extraction")]
+    #[test]
+    #[serial]
+    fn find_leaks_in_binary_file_exe() {
+        // Gather potential leaks
+        let root_dir_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(FILE_LIST_PROJ_PATH);
+        let file_list_db = FileListDatabase::new(
+            &[root_dir_path.join("main.cc")],
+            vec![
+                "-DDEF_TEST".to_string(),
+                format!("-I{}", FILE_LIST_PROJ_PATH),
+            ],
+        );
+        let potential_leaks = extract_artifacts_from_source_files(
+            file_list_db
+                .get_all_compile_commands()
+                .expect("get_all_compile_commands failed"),
+            file_list_db.is_file_path_in_arguments(),
+            &ExtractionOptions {
+                minimum_leak_size: 0,
+                ..Default::default()
+            },
+            None,
+        )
+        .expect("extract_artifacts_from_source_files failed");
+
+        // Look for leaks present in the compiled binary
+        let bin_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join(FILE_LIST_PROJ_PATH)
+            .join("a.exe");
+
+        let confirmed_leaks: BTreeSet<ConfirmedLeakWithUniqueLocation> = find_leaks_in_binary_file(
+            &bin_path,
+            &potential_leaks,
+            &leak_matcher(&potential_leaks),
+            &SectionFilter::default(),
+        )
+        .expect("find_leaks_in_binary_file failed");
+
+        let expected_string_literals = vec![
+            // main.cc
+            "included_string_literal",
+            "included_string_literal",
+            "MyStruct",
+            "MyStruct",
+            "MyStruct",
+            "MyClass",
+            "MyClass",
+            "MyClass",
+            "preprocessor_string_literal",
+            "preprocessor_string_literal",
+            r#"%s\n"#,
+        ];
+
+        // Check extracted string literals
+        assert!(confirmed_leaks.iter().enumerate().all(|(i, leak)| {
+            println!("{:?}", leak.data);
+            *leak.data == expected_string_literals[i]
+        }));
+        assert_eq!(confirmed_leaks.len(), expected_string_literals.len());
+    }
+
+    #[cfg(all(unix, feature = "extraction"))]
+    #[test]
+    #[serial]
+    fn find_leaks_in_binary_file_elf() {
+        // Gather potential leaks
+        let root_dir_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(FILE_LIST_PROJ_PATH);
+        let file_list_db = FileListDatabase::new(
+            &[root_dir_path.join("main.cc")],
+            vec!["-DDEF_TEST".to_string()],
+        );
+        let potential_leaks = extract_artifacts_from_source_files(
+            file_list_db
+                .get_all_compile_commands()
+                .expect("get_all_compile_commands failed"),
+            file_list_db.is_file_path_in_arguments(),
+            &ExtractionOptions {
+                minimum_leak_size: 0,
+                ..Default::default()
+            },
+            None,
+        )
+        .expect("extract_artifacts_from_source_files failed");
+
+        // Look for leaks present in the compiled binary
+        let bin_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join(FILE_LIST_PROJ_PATH)
+            .join("a.out");
+
+        let confirmed_leaks: BTreeSet<ConfirmedLeakWithUniqueLocation> = find_leaks_in_binary_file(
+            &bin_path,
+            &potential_leaks,
+            &leak_matcher(&potential_leaks),
+            &SectionFilter::default(),
+        )
+        .expect("find_leaks_in_binary_file failed");
+
+        let expected_string_literals = vec![
+            // main.cc
+            "included_string_literal",
+            "included_string_literal",
+            "included_string_literal",
+            "included_string_literal",
+            "MyStruct",
+            "MyStruct",
+            "MyStruct",
+            "MyStruct",
+            "MyStruct",
+            "MyStruct",
+            "MyStruct",
+            "MyStruct",
+            "MyStruct",
+            "MyStruct",
+            "MyStruct",
+            "MyStruct",
+            "MyStruct",
+            "MyStruct",
+            "MyClass",
+            "MyClass",
+            "MyClass",
+            "MyClass",
+            "MyClass",
+            "MyClass",
+            "MyClass",
+            "MyClass",
+            "MyClass",
+            "MyClass",
+            "MyClass",
+            "MyClass",
+            "MyClass",
+            "MyClass",
+            "MyClass",
+            "MyClass",
+            "MyClass",
+            "MyClass",
+            "my_method",
+            "my_static_member",
+            "my_method",
+            "my_static_member",
+            "MyEnum",
+            "MyEnum",
+            "MyEnum",
+            "MyEnum",
+            "MyEnumClass",
+            "MyEnumClass",
+            "my_namespace",
+            "my_namespace",
+            "my_namespaced_variable",
+            "anonymous_namespace_variable",
+            "main",
+            "main",
+            "main",
+            "main",
+            "main",
+            "main",
+            "preprocessor_string_literal",
+            r#"%s\n"#,
+            "preprocessor_string_literal",
+            "my_static_local",
+        ];
+
+        // Check extracted string literals
+        assert!(confirmed_leaks.iter().enumerate().all(|(i, leak)| {
+            println!("{:?}", leak.data);
+            *leak.data == expected_string_literals[i]
+        }));
+        assert_eq!(confirmed_leaks.len(), expected_string_literals.len());
+    }
+}
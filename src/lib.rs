@@ -0,0 +1,71 @@
+//! Cpplumber is a static analysis tool that helps detecting and keeping track
+//! of C and C++ source code information that leaks into compiled executable
+//! files.
+//!
+//! The `cpplumber` binary is a thin layer over this library, which can be used
+//! to integrate leak detection into other tools. The usual workflow is:
+//!
+//! 1. Extract potential leaks from source files with
+#![cfg_attr(
+    feature = "extraction",
+    doc = "   [`extraction::extract_artifacts_from_source_files`] (requires the"
+)]
+#![cfg_attr(
+    not(feature = "extraction"),
+    doc = "   `extraction::extract_artifacts_from_source_files` (requires the"
+)]
+//!    `extraction` feature, which depends on libclang), or load them from an
+//!    artifact file with [`artifact_file::read_artifact_file`].
+//! 2. Filter out suppressed artifacts with the functions of the
+//!    [`suppressions`] module.
+//! 3. Look for the remaining artifacts in binary files with
+//!    [`leak_search::find_leaks_in_binary_files`], which returns confirmed
+//!    leaks.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use std::collections::BTreeSet;
+//! use std::path::{Path, PathBuf};
+//!
+//! use cpplumber::artifact_file::read_artifact_file;
+//! use cpplumber::information_leak::ConfirmedLeakWithUniqueLocation;
+//! use cpplumber::leak_search::find_leaks_in_binary_files;
+//! use cpplumber::{MatchingEngine, SectionFilter};
+//!
+//! let potential_leaks = read_artifact_file(Path::new("artifacts.json"))?;
+//! let binary_file_paths = BTreeSet::from([PathBuf::from("program.exe")]);
+//! let leaks: Vec<ConfirmedLeakWithUniqueLocation> = find_leaks_in_binary_files(
+//!     &binary_file_paths,
+//!     &potential_leaks,
+//!     MatchingEngine::default(),
+//!     &SectionFilter::default(),
+//! )?;
+//! println!("{} leak(s) found", leaks.len());
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
+#[cfg(feature = "extraction")]
+pub(crate) mod artifact_cache;
+pub mod artifact_file;
+pub mod baseline;
+pub(crate) mod binary_format;
+#[cfg(feature = "extraction")]
+pub mod compilation_database;
+#[cfg(feature = "extraction")]
+pub mod extraction;
+pub mod information_leak;
+pub mod leak_search;
+pub(crate) mod pattern_matching;
+pub mod reporting;
+pub mod suppressions;
+
+#[cfg(feature = "extraction")]
+pub use artifact_cache::ArtifactCache;
+pub use binary_format::SectionFilter;
+#[cfg(feature = "extraction")]
+pub use compilation_database::CompilationDatabase;
+pub use information_leak::{ConfirmedLeak, PotentialLeak};
+pub use leak_search::LeakMatcher;
+pub use pattern_matching::{MatchingEngine, PatternMatch, PatternMatcher, MATCHING_ENGINE_NAMES};
+pub use suppressions::Suppressions;
@@ -1,62 +1,211 @@
 mod cli;
-mod compilation_database;
-mod information_leak;
-mod reporting;
-mod suppressions;
 
 use std::{
-    collections::{BTreeSet, HashMap},
-    fs::File,
-    io::Read,
+    collections::BTreeSet,
+    ops::Deref,
     path::{Path, PathBuf},
-    sync::Arc,
-    vec,
 };
 
 use anyhow::{anyhow, Context, Result};
-use clang::{Clang, Entity, EntityKind, Index};
-use rayon::prelude::*;
+use serde::Serialize;
 use structopt::StructOpt;
 
-use compilation_database::CompileCommands;
-use information_leak::{BinaryLocation, ConfirmedLeak};
-use reporting::dump_confirmed_leaks;
-use suppressions::Suppressions;
-
-use crate::{
-    cli::CpplumberOptions,
-    compilation_database::{generate_compilation_database, ProjectConfiguration},
+use cpplumber::{
+    artifact_file::{read_artifact_file, write_artifact_file},
+    baseline::Baseline,
     information_leak::{
-        ConfirmedLeakWithUniqueLocation, ConfirmedLeakWithUniqueValue, PotentialLeak,
+        ConfirmedLeak, ConfirmedLeakWithUniqueLocation, ConfirmedLeakWithUniqueValue, PotentialLeak,
+    },
+    leak_search::{find_leaks_in_binary_files, gather_binary_file_paths},
+    reporting::{diff_reports, dump_confirmed_leaks, dump_report_diff, read_json_report},
+    suppressions::{
+        filter_suppressed_artifacts_by_origin, filter_suppressed_artifacts_by_value,
+        parse_suppressions_file, Suppressions,
     },
-    suppressions::parse_suppressions_file,
+    SectionFilter,
 };
 
+use cli::{Command, CommonOptions, CpplumberOptions, ExtractionOptions, MatchingOptions};
+
 fn main() -> Result<()> {
     // Default to 'info' if 'RUST_LOG' is not set
     env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
 
     // Parse command-line options
     let options = CpplumberOptions::from_args();
-    let minimum_leak_size = options.minimum_leak_size.unwrap_or(4);
+    match options.command {
+        Some(Command::Extract {
+            output_file_path,
+            common,
+            extraction,
+        }) => run_extract_command(&output_file_path, &common, &extraction),
+        Some(Command::Match {
+            artifact_file_path,
+            common,
+            matching,
+        }) => run_match_command(&artifact_file_path, &common, &matching),
+        Some(Command::Diff {
+            old_report_path,
+            new_report_path,
+            json_output,
+        }) => run_diff_command(&old_report_path, &new_report_path, json_output),
+        None => run_default_command(&options.common, &options.extraction, &options.matching),
+    }
+}
 
+/// Extracts artifacts from source files and looks for them in binary files.
+fn run_default_command(
+    common_options: &CommonOptions,
+    extraction_options: &ExtractionOptions,
+    matching_options: &MatchingOptions,
+) -> Result<()> {
     // Initial checks before starting work
-    if !options.binary_file_path.is_file() {
-        return Err(anyhow!(
-            "'{}' is not a valid file path.",
-            options.binary_file_path.display()
-        ));
+    let leak_search = prepare_leak_search(matching_options)?;
+    let suppressions = apply_common_options(common_options)?;
+
+    let potential_leaks = extract_potential_leaks(extraction_options, &suppressions)?;
+    let potential_leaks = filter_suppressed_artifacts(potential_leaks, &suppressions);
+
+    find_and_report_leaks(&leak_search, &potential_leaks, matching_options)
+}
+
+/// Extracts artifacts from source files and saves them into an artifact file.
+fn run_extract_command(
+    output_file_path: &Path,
+    common_options: &CommonOptions,
+    extraction_options: &ExtractionOptions,
+) -> Result<()> {
+    let suppressions = apply_common_options(common_options)?;
+
+    let potential_leaks = extract_potential_leaks(extraction_options, &suppressions)?;
+    let potential_leaks = filter_suppressed_artifacts(potential_leaks, &suppressions);
+
+    log::info!(
+        "Saving {} artifacts to '{}'...",
+        potential_leaks.len(),
+        output_file_path.display()
+    );
+    write_artifact_file(output_file_path, &potential_leaks).with_context(|| {
+        format!(
+            "Failed to write artifact file '{}'",
+            output_file_path.display()
+        )
+    })
+}
+
+/// Loads artifacts from an artifact file and looks for them in binary files.
+fn run_match_command(
+    artifact_file_path: &Path,
+    common_options: &CommonOptions,
+    matching_options: &MatchingOptions,
+) -> Result<()> {
+    // Initial checks before starting work
+    let leak_search = prepare_leak_search(matching_options)?;
+    let suppressions = apply_common_options(common_options)?;
+
+    log::info!(
+        "Loading artifacts from '{}'...",
+        artifact_file_path.display()
+    );
+    let potential_leaks = read_artifact_file(artifact_file_path).with_context(|| {
+        format!(
+            "Failed to read artifact file '{}'",
+            artifact_file_path.display()
+        )
+    })?;
+    let potential_leaks = filter_suppressed_artifacts(potential_leaks, &suppressions);
+
+    find_and_report_leaks(&leak_search, &potential_leaks, matching_options)
+}
+
+/// Compares two JSON reports and prints the differences.
+fn run_diff_command(old_report_path: &Path, new_report_path: &Path, json: bool) -> Result<()> {
+    let read_report = |report_path: &Path| {
+        read_json_report(report_path)
+            .with_context(|| format!("Failed to read report '{}'", report_path.display()))
+    };
+    let diff = diff_reports(read_report(old_report_path)?, read_report(new_report_path)?);
+
+    dump_report_diff(std::io::stdout(), &diff, json)
+}
+
+/// Configures the thread pool and parses the suppression list if used.
+fn apply_common_options(options: &CommonOptions) -> Result<Option<Suppressions>> {
+    // Limit the number of worker threads if requested
+    if let Some(jobs) = options.jobs {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(jobs)
+            .build_global()
+            .with_context(|| "Failed to initialize the thread pool")?;
     }
 
     // Parse the suppression list if used
-    let suppressions = if let Some(ref suppressions_list) = options.suppressions_list {
+    if let Some(ref suppressions_list) = options.suppressions_list {
         log::info!("Parsing suppressions file...");
-        Some(
+        Ok(Some(
             parse_suppressions_file(suppressions_list)
                 .with_context(|| "Failed to parse suppressions list")?,
-        )
+        ))
     } else {
-        None
+        Ok(None)
+    }
+}
+
+/// Parameters of the search for leaks in binary files
+struct LeakSearch {
+    binary_file_paths: BTreeSet<PathBuf>,
+    section_filter: SectionFilter,
+    /// Known leaks, which aren't reported
+    baseline: Option<Baseline>,
+}
+
+/// Gathers binary files, compiles section filters and loads the baseline, so
+/// that invalid options are reported before starting work.
+fn prepare_leak_search(options: &MatchingOptions) -> Result<LeakSearch> {
+    let binary_file_paths = gather_binary_file_paths(&options.binary_file_path_globs)?;
+    let section_filter = SectionFilter::new(&options.only_sections, &options.exclude_sections)
+        .with_context(|| "Invalid section filter")?;
+    let baseline =
+        if let Some(ref baseline_path) = options.baseline_path {
+            log::info!("Loading baseline from '{}'...", baseline_path.display());
+            Some(Baseline::from_json_report(baseline_path).with_context(|| {
+                format!("Failed to load baseline '{}'", baseline_path.display())
+            })?)
+        } else {
+            None
+        };
+
+    Ok(LeakSearch {
+        binary_file_paths,
+        section_filter,
+        baseline,
+    })
+}
+
+/// Gathers the source files described by `options`, and extracts information
+/// that could leak from them. Suppressed files aren't parsed.
+#[cfg(feature = "extraction")]
+fn extract_potential_leaks(
+    options: &ExtractionOptions,
+    suppressions: &Option<Suppressions>,
+) -> Result<Vec<PotentialLeak>> {
+    use cpplumber::{
+        compilation_database::{generate_compilation_database, ProjectConfiguration},
+        extraction::{self, extract_artifacts_from_source_files},
+        suppressions::filter_suppressed_files,
+        ArtifactCache,
+    };
+
+    let extraction_options = extraction::ExtractionOptions {
+        ignore_system_headers: !options.report_system_headers,
+        ignore_string_literals: options.ignore_string_literals,
+        ignore_struct_names: options.ignore_struct_names,
+        ignore_function_names: options.ignore_function_names,
+        ignore_enum_names: options.ignore_enum_names,
+        ignore_enum_constant_names: options.ignore_enum_constant_names,
+        ignore_variable_names: options.ignore_variable_names,
+        ignore_namespace_names: options.ignore_namespace_names,
+        minimum_leak_size: options.minimum_leak_size.unwrap_or(4),
     };
 
     log::info!("Gathering source files...");
@@ -77,575 +226,116 @@ fn main() -> Result<()> {
     // Filter suppressed files from the list, to avoid parsing files we're not
     // interested in
     let compile_commands =
-        filter_suppressed_files(compilation_db.get_all_compile_commands()?, &suppressions);
+        filter_suppressed_files(compilation_db.get_all_compile_commands()?, suppressions);
+
+    // Prepare the artifact cache, if requested
+    let artifact_cache = if let Some(cache_dir_path) = &options.cache_dir {
+        // Options that change which artifacts are extracted
+        let extraction_settings = format!("{:?}", extraction_options);
+        let artifact_cache = ArtifactCache::new(cache_dir_path, extraction_settings)?;
+        Some(if options.no_cache {
+            artifact_cache.refresh()
+        } else {
+            artifact_cache
+        })
+    } else {
+        None
+    };
 
     log::info!("Extracting artifacts from source files...");
     // Parse source files and extract information that could leak
-    let potential_leaks = extract_artifacts_from_source_files(
+    extract_artifacts_from_source_files(
         compile_commands,
         compilation_db.is_file_path_in_arguments(),
-        !options.report_system_headers,
-        options.ignore_string_literals,
-        options.ignore_struct_names,
-        minimum_leak_size,
-    )?;
+        &extraction_options,
+        artifact_cache.as_ref(),
+    )
+}
+
+#[cfg(not(feature = "extraction"))]
+fn extract_potential_leaks(
+    _options: &ExtractionOptions,
+    _suppressions: &Option<Suppressions>,
+) -> Result<Vec<PotentialLeak>> {
+    Err(anyhow!(
+        "This build doesn't support extracting artifacts from source files \
+         (the `extraction` feature is disabled), use the `match` subcommand instead"
+    ))
+}
 
+/// Filters suppressed artifacts by source location and by value
+fn filter_suppressed_artifacts(
+    potential_leaks: Vec<PotentialLeak>,
+    suppressions: &Option<Suppressions>,
+) -> Vec<PotentialLeak> {
     log::info!("Filtering suppressed artifacts...");
     // Filter suppressed artifacts by source location if needed
     // Note: We need to do this "again" because artifacts from suppressed
     // headers might have been included during the parsing of other files
-    let potential_leaks = filter_suppressed_artifacts_by_origin(potential_leaks, &suppressions);
+    let potential_leaks = filter_suppressed_artifacts_by_origin(potential_leaks, suppressions);
     // Filter suppressed artifacts by value if needed
-    let potential_leaks = filter_suppressed_artifacts_by_value(potential_leaks, &suppressions);
+    let potential_leaks = filter_suppressed_artifacts_by_value(potential_leaks, suppressions);
 
-    log::info!(
-        "Looking for leaks in '{}'...",
-        options.binary_file_path.display()
-    );
     log::debug!("{:#?}", potential_leaks);
+    potential_leaks
+}
+
+/// Looks for leaks in binary files and reports them. Returns an error if leaks
+/// were found.
+fn find_and_report_leaks(
+    leak_search: &LeakSearch,
+    potential_leaks: &[PotentialLeak],
+    options: &MatchingOptions,
+) -> Result<()> {
     if options.ignore_multiple_locations {
         // Find leaks and deduplicate based on their value
-        let leaks: BTreeSet<ConfirmedLeakWithUniqueValue> =
-            find_leaks_in_binary_file(&options.binary_file_path, potential_leaks)?;
-        log::debug!("Done!");
-
-        if leaks.is_empty() {
-            // Nothing leaked, alright!
-            Ok(())
-        } else {
-            // Print the result to stdout
-            dump_confirmed_leaks(std::io::stdout(), leaks, options.json_output)?;
-
-            // Return an error to indicate that leaks were found (useful for automation)
-            Err(anyhow!("Leaks detected!"))
-        }
+        report_leaks::<ConfirmedLeakWithUniqueValue>(leak_search, potential_leaks, options)
     } else {
         // Find leaks and deduplicate based on their location (source + binary)
-        let leaks: BTreeSet<ConfirmedLeakWithUniqueLocation> =
-            find_leaks_in_binary_file(&options.binary_file_path, potential_leaks)?;
-        log::debug!("Done!");
-
-        if leaks.is_empty() {
-            // Nothing leaked, alright!
-            Ok(())
-        } else {
-            // Print the result to stdout
-            dump_confirmed_leaks(std::io::stdout(), leaks, options.json_output)?;
-
-            // Return an error to indicate that leaks were found (useful for automation)
-            Err(anyhow!("Leaks detected!"))
-        }
-    }
-}
-
-fn gather_entities_by_kind<'tu>(
-    root_entity: Entity<'tu>,
-    entity_kind_filter: &[EntityKind],
-    ignore_system_headers: bool,
-) -> Vec<Entity<'tu>> {
-    gather_entities_by_kind_rec(root_entity, entity_kind_filter, ignore_system_headers)
-}
-
-fn gather_entities_by_kind_rec<'tu>(
-    root_entity: Entity<'tu>,
-    entity_kind_filter: &[EntityKind],
-    ignore_system_headers: bool,
-) -> Vec<Entity<'tu>> {
-    let mut entities = vec![];
-
-    let root_entity_kind = root_entity.get_kind();
-    // Check the if entity's kind is one we're looking for
-    if entity_kind_filter
-        .iter()
-        .any(|elem| elem == &root_entity_kind)
-    {
-        entities.push(root_entity);
-    }
-
-    for child in root_entity.get_children() {
-        // Ignore entity if requested
-        if ignore_system_headers && child.is_in_system_header() {
-            continue;
-        }
-
-        let entities_sub =
-            gather_entities_by_kind_rec(child, entity_kind_filter, ignore_system_headers);
-        entities.extend(entities_sub);
-    }
-
-    entities
-}
-
-fn filter_suppressed_files(
-    compile_cmds: CompileCommands,
-    suppressions: &Option<Suppressions>,
-) -> CompileCommands {
-    if let Some(suppressions) = suppressions {
-        compile_cmds
-            .into_par_iter()
-            .filter(|compile_cmd| {
-                if let Some(file_path) = compile_cmd.filename.to_str() {
-                    !suppressions
-                        .files
-                        .par_iter()
-                        .any(|pattern| pattern.matches(file_path))
-                } else {
-                    true
-                }
-            })
-            .collect()
-    } else {
-        compile_cmds
-    }
-}
-
-fn extract_artifacts_from_source_files(
-    compile_commands: CompileCommands,
-    use_file_path_from_arguments: bool,
-    ignore_system_headers: bool,
-    ignore_string_literals: bool,
-    ignore_struct_names: bool,
-    minimum_leak_size: usize,
-) -> Result<Vec<PotentialLeak>> {
-    // Prepare the clang index
-    let clang = Clang::new().map_err(|e| anyhow!(e))?;
-    let index = Index::new(&clang, false, false);
-
-    compile_commands
-        .into_iter()
-        // Populate indexes by parsing source files in parallel
-        .try_fold(
-            Vec::new(),
-            |mut accum, compile_cmd| -> Result<Vec<PotentialLeak>> {
-                // Note: For some reason, having the file path in `arguments` when
-                // passing the file path explicitly to libclang make the parser fail.
-                // So we explicitely avoid doing so.
-                let file_path = if use_file_path_from_arguments {
-                    PathBuf::default()
-                } else {
-                    compile_cmd.filename
-                };
-                let translation_unit = index
-                    .parser(&file_path)
-                    .arguments(&compile_cmd.arguments)
-                    .parse()
-                    .with_context(|| {
-                        format!("Failed to parse source file '{}'", file_path.display())
-                    })?;
-
-                // Setup filter
-                let mut entity_kind_filter = vec![];
-                if !ignore_string_literals {
-                    entity_kind_filter.push(EntityKind::StringLiteral);
-                }
-                if !ignore_struct_names {
-                    entity_kind_filter.push(EntityKind::StructDecl);
-                    entity_kind_filter.push(EntityKind::ClassDecl);
-                }
-
-                // Gather entities
-                let string_literals = gather_entities_by_kind(
-                    translation_unit.get_entity(),
-                    &entity_kind_filter,
-                    ignore_system_headers,
-                );
-
-                accum.extend(string_literals.into_iter().filter_map(|literal| {
-                    let leak_res: Result<PotentialLeak> = literal.try_into();
-                    if let Ok(potential_leak) = leak_res {
-                        if potential_leak.bytes.len() >= minimum_leak_size {
-                            Some(potential_leak)
-                        } else {
-                            // Value is too small, ignore it
-                            None
-                        }
-                    } else {
-                        // Log failure and discard element
-                        log::warn!(
-                            "Failed to process entity '{:?}': {}",
-                            literal,
-                            leak_res.unwrap_err()
-                        );
-                        None
-                    }
-                }));
-
-                Ok(accum)
-            },
-        )
-}
-
-fn filter_suppressed_artifacts_by_origin(
-    potential_leaks: Vec<PotentialLeak>,
-    suppressions: &Option<Suppressions>,
-) -> Vec<PotentialLeak> {
-    if let Some(suppressions) = suppressions {
-        potential_leaks
-            .into_par_iter()
-            .filter(|leak| {
-                let file_path = &leak.declaration_metadata.file;
-                if let Some(file_path) = file_path.as_os_str().to_str() {
-                    !suppressions
-                        .files
-                        .par_iter()
-                        .any(|pattern| pattern.matches(file_path))
-                } else {
-                    true
-                }
-            })
-            .collect()
-    } else {
-        potential_leaks
+        report_leaks::<ConfirmedLeakWithUniqueLocation>(leak_search, potential_leaks, options)
     }
 }
 
-fn filter_suppressed_artifacts_by_value(
-    potential_leaks: Vec<PotentialLeak>,
-    suppressions: &Option<Suppressions>,
-) -> Vec<PotentialLeak> {
-    if let Some(suppressions) = suppressions {
-        potential_leaks
-            .into_par_iter()
-            .filter(|leak| !suppressions.artifacts.contains(&leak.data))
-            .collect()
-    } else {
-        potential_leaks
-    }
-}
-
-fn find_leaks_in_binary_file<PotentialLeakCollection, SortedConfirmedLeak>(
-    binary_file_path: &Path,
-    leak_desc: PotentialLeakCollection,
-) -> Result<BTreeSet<SortedConfirmedLeak>>
+fn report_leaks<SortedConfirmedLeak>(
+    leak_search: &LeakSearch,
+    potential_leaks: &[PotentialLeak],
+    options: &MatchingOptions,
+) -> Result<()>
 where
-    PotentialLeakCollection: IntoParallelIterator<Item = PotentialLeak>,
-    SortedConfirmedLeak: From<ConfirmedLeak> + Ord + Eq + Send,
+    SortedConfirmedLeak: From<ConfirmedLeak>
+        + Into<ConfirmedLeak>
+        + Deref<Target = ConfirmedLeak>
+        + Ord
+        + Eq
+        + Send
+        + Serialize,
 {
-    // Read binary file's content
-    let mut bin_file = File::open(binary_file_path)?;
-    let mut bin_data = vec![];
-    bin_file.read_to_end(&mut bin_data)?;
-
-    // Build a map that allows to lookup "leaks' first byte -> leaks"
-    let byte_to_leaks = leak_desc
-        .into_par_iter()
-        .fold(
-            HashMap::new,
-            |mut accum: HashMap<u8, Vec<PotentialLeak>>, potential_leak| {
-                if let Some(key) = potential_leak.bytes.first() {
-                    if let Some(value) = accum.get_mut(key) {
-                        value.push(potential_leak);
-                    } else {
-                        accum.insert(*key, vec![potential_leak]);
-                    }
-                }
-
-                accum
-            },
-        )
-        // Reduce intermediate maps into one
-        .reduce(HashMap::new, |mut accum, other| {
-            for (other_key, mut other_value) in other {
-                if let Some(value) = accum.get_mut(&other_key) {
-                    value.append(&mut other_value);
-                } else {
-                    accum.insert(other_key, other_value);
-                }
-            }
-            accum
-        });
-
-    // Go through the binary file byte by byte and try to match leaks that start
-    // with each byte
-    let shared_binary_file_path = Arc::new(binary_file_path.to_path_buf().canonicalize()?);
-    let confirmed_leaks = bin_data
-        .par_iter()
-        .enumerate()
-        // Find actual leaks
-        .map(|(i, byte_value)| {
-            let mut confirmed_leaks = BTreeSet::new();
-            if let Some(potential_leaks) = byte_to_leaks.get(byte_value) {
-                // Go through each candidate
-                for leak in potential_leaks {
-                    // Check bounds
-                    if i + leak.bytes.len() <= bin_data.len() {
-                        let byte_slice = &bin_data[i..i + leak.bytes.len()];
-                        if byte_slice == leak.bytes {
-                            // Bytes match, the leak is confirmed
-                            confirmed_leaks.insert(SortedConfirmedLeak::from(ConfirmedLeak {
-                                data_type: leak.data_type,
-                                data: leak.data.clone(),
-                                location: information_leak::LeakLocation {
-                                    source: leak.declaration_metadata.clone(),
-                                    binary: BinaryLocation {
-                                        file: shared_binary_file_path.clone(),
-                                        offset: i as u64,
-                                    },
-                                },
-                            }));
-                        }
-                    }
-                }
-            }
-
-            confirmed_leaks
-        })
-        .reduce(BTreeSet::new, |mut accum, other| {
-            accum.extend(other);
-            accum
-        });
-
-    Ok(confirmed_leaks)
-}
-
-#[cfg(test)]
-mod tests {
-    use crate::compilation_database::{CompilationDatabase, FileListDatabase};
-
-    use super::*;
-
-    use serial_test::serial;
-
-    const FILE_LIST_PROJ_PATH: &str = "tests/data/main/file_list_proj";
-
-    #[test]
-    #[serial]
-    fn extract_artifacts_from_source_files_file_list() {
-        let root_dir_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(FILE_LIST_PROJ_PATH);
-        let file_list_db = FileListDatabase::new(
-            &[root_dir_path.join("main.cc")],
-            vec![
-                "-DDEF_TEST".to_string(),
-                format!("-I{}", FILE_LIST_PROJ_PATH),
-            ],
+    let mut leaks: Vec<SortedConfirmedLeak> = find_leaks_in_binary_files(
+        &leak_search.binary_file_paths,
+        potential_leaks,
+        options.matching_engine,
+        &leak_search.section_filter,
+    )?;
+    log::debug!("Done!");
+
+    // Ignore known leaks
+    if let Some(ref baseline) = leak_search.baseline {
+        let leak_count = leaks.len();
+        leaks.retain(|leak| !baseline.contains(leak));
+        log::info!(
+            "Ignored {} leak(s) present in the baseline",
+            leak_count - leaks.len()
         );
-        let potential_leaks = extract_artifacts_from_source_files(
-            file_list_db
-                .get_all_compile_commands()
-                .expect("get_all_compile_commands failed"),
-            file_list_db.is_file_path_in_arguments(),
-            true,
-            false,
-            false,
-            0,
-        )
-        .expect("extract_artifacts_from_source_files failed");
-
-        let expected_string_literals = vec![
-            "included_string_literal",
-            "c_string",
-            "utf8_string",
-            "wide_string",
-            "utf16_string",
-            "utf32_string",
-            "raw_string",
-            "raw_utf8_string",
-            "wide_raw_string",
-            "raw_utf16_string",
-            "raw_utf32_string",
-            "def_test",
-            "concatenated_string",
-            r#"multiline\nstring"#,
-            r#"'\"\n\t\a\b|\220|\220|\351\246\231|\351\246\231|\360\237\230\202"#,
-            "MyStruct",
-            "",
-            "MyClass",
-            "",
-            r#"%s\n"#,
-            "preprocessor_string_literal",
-            r#"%s\n"#,
-            "preprocessor_string_literal",
-            r#"%s\n"#,
-        ];
-
-        // Check extracted string literals
-        assert!(potential_leaks.iter().enumerate().all(|(i, leak)| {
-            println!("{:?}", leak.data);
-            *leak.data == expected_string_literals[i]
-        }));
-        assert_eq!(expected_string_literals.len(), potential_leaks.len());
     }
 
-    #[test]
-    #[serial]
-    fn extract_artifacts_with_minimum_leak_size() {
-        let root_dir_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(FILE_LIST_PROJ_PATH);
-        let file_list_db = FileListDatabase::new(
-            &[root_dir_path.join("main.cc")],
-            vec![
-                "-DDEF_TEST".to_string(),
-                format!("-I{}", FILE_LIST_PROJ_PATH),
-            ],
-        );
-        let potential_leaks = extract_artifacts_from_source_files(
-            file_list_db
-                .get_all_compile_commands()
-                .expect("get_all_compile_commands failed"),
-            file_list_db.is_file_path_in_arguments(),
-            true,
-            false,
-            false,
-            4,
-        )
-        .expect("extract_artifacts_from_source_files failed");
-
-        // r#""%s\n""# should be removed
-        let expected_string_literals = vec![
-            // main.cc
-            "included_string_literal",
-            "c_string",
-            "utf8_string",
-            "wide_string",
-            "utf16_string",
-            "utf32_string",
-            "raw_string",
-            "raw_utf8_string",
-            "wide_raw_string",
-            "raw_utf16_string",
-            "raw_utf32_string",
-            "def_test",
-            "concatenated_string",
-            r#"multiline\nstring"#,
-            r#"'\"\n\t\a\b|\220|\220|\351\246\231|\351\246\231|\360\237\230\202"#,
-            "MyStruct",
-            "MyClass",
-            "preprocessor_string_literal",
-            r#"%s\n"#,
-            "preprocessor_string_literal",
-        ];
-
-        // Check extracted string literals
-        assert!(potential_leaks.iter().enumerate().all(|(i, leak)| {
-            println!("{:?}", leak.data);
-            *leak.data == expected_string_literals[i]
-        }));
-        assert_eq!(expected_string_literals.len(), potential_leaks.len());
-    }
-
-    #[cfg(windows)]
-    #[test]
-    #[serial]
-    fn find_leaks_in_binary_file_exe() {
-        // Gather potential leaks
-        let root_dir_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(FILE_LIST_PROJ_PATH);
-        let file_list_db = FileListDatabase::new(
-            &[root_dir_path.join("main.cc")],
-            vec![
-                "-DDEF_TEST".to_string(),
-                format!("-I{}", FILE_LIST_PROJ_PATH),
-            ],
-        );
-        let potential_leaks = extract_artifacts_from_source_files(
-            file_list_db
-                .get_all_compile_commands()
-                .expect("get_all_compile_commands failed"),
-            file_list_db.is_file_path_in_arguments(),
-            true,
-            false,
-            false,
-            0,
-        )
-        .expect("extract_artifacts_from_source_files failed");
-
-        // Look for leaks present in the compiled binary
-        let bin_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-            .join(FILE_LIST_PROJ_PATH)
-            .join("a.exe");
-
-        let confirmed_leaks: BTreeSet<ConfirmedLeakWithUniqueLocation> =
-            find_leaks_in_binary_file(&bin_path, potential_leaks)
-                .expect("find_leaks_in_binary_file failed");
-
-        let expected_string_literals = vec![
-            // main.cc
-            "included_string_literal",
-            "MyStruct",
-            "MyStruct",
-            "MyStruct",
-            "MyClass",
-            "MyClass",
-            "MyClass",
-            "preprocessor_string_literal",
-            "preprocessor_string_literal",
-            r#"%s\n"#,
-        ];
-
-        // Check extracted string literals
-        assert!(confirmed_leaks.iter().enumerate().all(|(i, leak)| {
-            println!("{:?}", leak.data);
-            *leak.data == expected_string_literals[i]
-        }));
-        assert_eq!(confirmed_leaks.len(), expected_string_literals.len());
-    }
+    if leaks.is_empty() {
+        // Nothing leaked, alright!
+        Ok(())
+    } else {
+        // Print the result to stdout
+        dump_confirmed_leaks(std::io::stdout(), leaks, &options.report_options())?;
 
-    #[cfg(unix)]
-    #[test]
-    #[serial]
-    fn find_leaks_in_binary_file_elf() {
-        // Gather potential leaks
-        let root_dir_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(FILE_LIST_PROJ_PATH);
-        let file_list_db = FileListDatabase::new(
-            &[root_dir_path.join("main.cc")],
-            vec!["-DDEF_TEST".to_string()],
-        );
-        let potential_leaks = extract_artifacts_from_source_files(
-            file_list_db
-                .get_all_compile_commands()
-                .expect("get_all_compile_commands failed"),
-            file_list_db.is_file_path_in_arguments(),
-            true,
-            false,
-            false,
-            0,
-        )
-        .expect("extract_artifacts_from_source_files failed");
-
-        // Look for leaks present in the compiled binary
-        let bin_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-            .join(FILE_LIST_PROJ_PATH)
-            .join("a.out");
-
-        let confirmed_leaks: BTreeSet<ConfirmedLeakWithUniqueLocation> =
-            find_leaks_in_binary_file(&bin_path, potential_leaks)
-                .expect("find_leaks_in_binary_file failed");
-
-        let expected_string_literals = vec![
-            // main.cc
-            "included_string_literal",
-            "included_string_literal",
-            "MyStruct",
-            "MyStruct",
-            "MyStruct",
-            "MyStruct",
-            "MyStruct",
-            "MyStruct",
-            "MyStruct",
-            "MyStruct",
-            "MyStruct",
-            "MyStruct",
-            "MyStruct",
-            "MyStruct",
-            "MyClass",
-            "MyClass",
-            "MyClass",
-            "MyClass",
-            "MyClass",
-            "MyClass",
-            "MyClass",
-            "MyClass",
-            "MyClass",
-            "MyClass",
-            "MyClass",
-            "preprocessor_string_literal",
-            r#"%s\n"#,
-            "preprocessor_string_literal",
-        ];
-
-        // Check extracted string literals
-        assert!(confirmed_leaks.iter().enumerate().all(|(i, leak)| {
-            println!("{:?}", leak.data);
-            *leak.data == expected_string_literals[i]
-        }));
-        assert_eq!(confirmed_leaks.len(), expected_string_literals.len());
+        // Return an error to indicate that leaks were found (useful for automation)
+        Err(anyhow!("Leaks detected!"))
     }
 }
@@ -0,0 +1,26 @@
+use aho_corasick::AhoCorasick;
+use anyhow::Result;
+
+use super::MultiPatternMatcher;
+
+/// Matches all patterns in a single pass over the data, using an Aho-Corasick
+/// automaton.
+pub struct AhoCorasickMatcher {
+    automaton: AhoCorasick,
+}
+
+impl AhoCorasickMatcher {
+    pub fn new(patterns: &[&[u8]]) -> Result<Self> {
+        Ok(Self {
+            automaton: AhoCorasick::new(patterns)?,
+        })
+    }
+}
+
+impl MultiPatternMatcher for AhoCorasickMatcher {
+    fn for_each_match(&self, haystack: &[u8], on_match: &mut dyn FnMut(usize, usize)) {
+        for found in self.automaton.find_overlapping_iter(haystack) {
+            on_match(found.pattern().as_usize(), found.start());
+        }
+    }
+}
@@ -0,0 +1,256 @@
+mod aho_corasick;
+mod naive;
+
+use std::{collections::HashMap, ops::Range, str::FromStr};
+
+use anyhow::{anyhow, Result};
+use rayon::prelude::*;
+
+pub use self::aho_corasick::AhoCorasickMatcher;
+pub use naive::NaiveMatcher;
+
+/// Names of the available matching engines, as expected on the command line
+pub const MATCHING_ENGINE_NAMES: &[&str] = &["aho-corasick", "naive"];
+/// Size of the chunks of data that are scanned in parallel
+const SCAN_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Describes the algorithm used to look for patterns in binary files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MatchingEngine {
+    /// Automaton-based matching, scales with the number of patterns
+    #[default]
+    AhoCorasick,
+    /// Candidate patterns are selected by their first byte and compared one
+    /// by one
+    Naive,
+}
+
+impl FromStr for MatchingEngine {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "aho-corasick" => Ok(Self::AhoCorasick),
+            "naive" => Ok(Self::Naive),
+            _ => Err(anyhow!("Unknown matching engine '{}'", s)),
+        }
+    }
+}
+
+pub trait MultiPatternMatcher: Send + Sync {
+    /// Calls `on_match` with the index of the pattern and the start offset of
+    /// every (possibly overlapping) occurrence of the patterns in `haystack`.
+    fn for_each_match(&self, haystack: &[u8], on_match: &mut dyn FnMut(usize, usize));
+}
+
+/// Occurrence of a pattern in some data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PatternMatch {
+    /// Offset of the first byte of the match in the data
+    pub offset: usize,
+    /// Index of the matched pattern, as passed to `PatternMatcher::new`
+    pub pattern_index: usize,
+}
+
+/// Looks for a set of byte patterns in data.
+pub struct PatternMatcher {
+    matcher: Box<dyn MultiPatternMatcher>,
+    /// Indices of the original patterns that correspond to each unique
+    /// pattern given to `matcher`
+    pattern_indices: Vec<Vec<usize>>,
+    max_pattern_len: usize,
+}
+
+impl PatternMatcher {
+    /// Builds a matcher for the given patterns. Empty patterns never match.
+    pub fn new<P: AsRef<[u8]>>(engine: MatchingEngine, patterns: &[P]) -> Result<Self> {
+        // Patterns are deduplicated, so that identical patterns are only
+        // matched once
+        let mut unique_patterns: Vec<&[u8]> = vec![];
+        let mut pattern_indices: Vec<Vec<usize>> = vec![];
+        let mut pattern_to_unique_index = HashMap::new();
+        for (pattern_index, pattern) in patterns.iter().enumerate() {
+            let pattern = pattern.as_ref();
+            if pattern.is_empty() {
+                continue;
+            }
+            let unique_index = *pattern_to_unique_index.entry(pattern).or_insert_with(|| {
+                unique_patterns.push(pattern);
+                pattern_indices.push(vec![]);
+                unique_patterns.len() - 1
+            });
+            pattern_indices[unique_index].push(pattern_index);
+        }
+
+        let max_pattern_len = unique_patterns
+            .iter()
+            .map(|pattern| pattern.len())
+            .max()
+            .unwrap_or(0);
+        let matcher: Box<dyn MultiPatternMatcher> = match engine {
+            MatchingEngine::AhoCorasick => Box::new(AhoCorasickMatcher::new(&unique_patterns)?),
+            MatchingEngine::Naive => Box::new(NaiveMatcher::new(&unique_patterns)),
+        };
+
+        Ok(Self {
+            matcher,
+            pattern_indices,
+            max_pattern_len,
+        })
+    }
+
+    /// Returns all the (possibly overlapping) occurrences of the patterns that
+    /// are fully contained in `data[range]`, sorted by offset. Offsets are
+    /// relative to the start of `data`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cpplumber::{MatchingEngine, PatternMatch, PatternMatcher};
+    ///
+    /// let matcher = PatternMatcher::new(MatchingEngine::default(), &["secret", "cret"])?;
+    /// let data = b"my secret data";
+    /// assert_eq!(
+    ///     matcher.find_matches(data, 0..data.len()),
+    ///     vec![
+    ///         PatternMatch { offset: 3, pattern_index: 0 },
+    ///         PatternMatch { offset: 5, pattern_index: 1 },
+    ///     ]
+    /// );
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    pub fn find_matches(&self, data: &[u8], range: Range<usize>) -> Vec<PatternMatch> {
+        self.find_matches_in_chunks(data, range, SCAN_CHUNK_SIZE)
+    }
+
+    fn find_matches_in_chunks(
+        &self,
+        data: &[u8],
+        range: Range<usize>,
+        chunk_size: usize,
+    ) -> Vec<PatternMatch> {
+        let chunk_starts: Vec<usize> = range.clone().step_by(chunk_size).collect();
+        let mut matches: Vec<PatternMatch> = chunk_starts
+            .into_par_iter()
+            .flat_map_iter(|chunk_start| {
+                let chunk_end = (chunk_start + chunk_size).min(range.end);
+                // Chunks overlap, so that matches that start at the end of a
+                // chunk can be found. Only matches that start in the chunk are
+                // kept, to avoid duplicates.
+                let window_end =
+                    (chunk_end + self.max_pattern_len.saturating_sub(1)).min(range.end);
+                let mut chunk_matches = vec![];
+                self.matcher.for_each_match(
+                    &data[chunk_start..window_end],
+                    &mut |unique_index, start| {
+                        let offset = chunk_start + start;
+                        if offset < chunk_end {
+                            chunk_matches.extend(self.pattern_indices[unique_index].iter().map(
+                                |&pattern_index| PatternMatch {
+                                    offset,
+                                    pattern_index,
+                                },
+                            ));
+                        }
+                    },
+                );
+                chunk_matches
+            })
+            .collect();
+        matches.sort_unstable();
+
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PATTERNS: &[&[u8]] = &[b"abc", b"bcd", b"", b"abc", b"c", b"cdxy"];
+    const DATA: &[u8] = b"xxabcdxyabc";
+
+    fn expected_matches() -> Vec<PatternMatch> {
+        [
+            (2, 0),
+            (2, 3),
+            (3, 1),
+            (4, 4),
+            (4, 5),
+            (8, 0),
+            (8, 3),
+            (10, 4),
+        ]
+        .iter()
+        .map(|&(offset, pattern_index)| PatternMatch {
+            offset,
+            pattern_index,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn matching_engine_from_str() {
+        for name in MATCHING_ENGINE_NAMES {
+            assert!(MatchingEngine::from_str(name).is_ok());
+        }
+        assert!(MatchingEngine::from_str("unknown").is_err());
+    }
+
+    #[test]
+    fn find_matches_aho_corasick() {
+        let matcher =
+            PatternMatcher::new(MatchingEngine::AhoCorasick, PATTERNS).expect("new failed");
+        assert_eq!(
+            matcher.find_matches(DATA, 0..DATA.len()),
+            expected_matches()
+        );
+    }
+
+    #[test]
+    fn find_matches_naive() {
+        let matcher = PatternMatcher::new(MatchingEngine::Naive, PATTERNS).expect("new failed");
+        assert_eq!(
+            matcher.find_matches(DATA, 0..DATA.len()),
+            expected_matches()
+        );
+    }
+
+    #[test]
+    fn find_matches_in_range() {
+        for engine in [MatchingEngine::AhoCorasick, MatchingEngine::Naive] {
+            let matcher = PatternMatcher::new(engine, PATTERNS).expect("new failed");
+            // Matches that aren't fully contained in the range are ignored
+            assert_eq!(
+                matcher.find_matches(DATA, 3..9),
+                vec![
+                    PatternMatch {
+                        offset: 3,
+                        pattern_index: 1
+                    },
+                    PatternMatch {
+                        offset: 4,
+                        pattern_index: 4
+                    },
+                    PatternMatch {
+                        offset: 4,
+                        pattern_index: 5
+                    },
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn find_matches_across_chunks() {
+        for engine in [MatchingEngine::AhoCorasick, MatchingEngine::Naive] {
+            let matcher = PatternMatcher::new(engine, PATTERNS).expect("new failed");
+            for chunk_size in 1..=DATA.len() {
+                assert_eq!(
+                    matcher.find_matches_in_chunks(DATA, 0..DATA.len(), chunk_size),
+                    expected_matches()
+                );
+            }
+        }
+    }
+}
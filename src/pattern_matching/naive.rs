@@ -0,0 +1,45 @@
+use std::collections::HashMap;
+
+use super::MultiPatternMatcher;
+
+/// Compares the data with all the patterns that start with the same byte, at
+/// every offset.
+pub struct NaiveMatcher {
+    patterns: Vec<Vec<u8>>,
+    /// Map that allows to lookup "patterns' first byte -> pattern indices"
+    byte_to_patterns: HashMap<u8, Vec<usize>>,
+}
+
+impl NaiveMatcher {
+    pub fn new(patterns: &[&[u8]]) -> Self {
+        let mut byte_to_patterns: HashMap<u8, Vec<usize>> = HashMap::new();
+        for (pattern_index, pattern) in patterns.iter().enumerate() {
+            if let Some(first_byte) = pattern.first() {
+                byte_to_patterns
+                    .entry(*first_byte)
+                    .or_default()
+                    .push(pattern_index);
+            }
+        }
+
+        Self {
+            patterns: patterns.iter().map(|pattern| pattern.to_vec()).collect(),
+            byte_to_patterns,
+        }
+    }
+}
+
+impl MultiPatternMatcher for NaiveMatcher {
+    fn for_each_match(&self, haystack: &[u8], on_match: &mut dyn FnMut(usize, usize)) {
+        for (offset, byte) in haystack.iter().enumerate() {
+            if let Some(pattern_indices) = self.byte_to_patterns.get(byte) {
+                // Go through each candidate
+                for &pattern_index in pattern_indices {
+                    if haystack[offset..].starts_with(&self.patterns[pattern_index]) {
+                        on_match(pattern_index, offset);
+                    }
+                }
+            }
+        }
+    }
+}
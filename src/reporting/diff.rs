@@ -0,0 +1,291 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+
+use anyhow::Result;
+use serde::Serialize;
+
+use super::{
+    display_confirmed_leak, display_leak_location, display_leaked_data_type, ReportVersion,
+    PKG_VERSION, REPORT_FORMAT_VERSION,
+};
+use crate::information_leak::{ConfirmedLeak, LeakLocation, LeakedDataType};
+
+/// Differences between the leaks of two reports
+#[derive(Default, Serialize)]
+pub struct ReportDiff {
+    /// Leaks only present in the new report
+    pub added: Vec<ConfirmedLeak>,
+    /// Leaks only present in the old report
+    pub removed: Vec<ConfirmedLeak>,
+    /// Leaks present in both reports, but at different locations
+    pub moved: Vec<MovedLeak>,
+}
+
+/// Leaked data found at different locations in two reports
+#[derive(Serialize)]
+pub struct MovedLeak {
+    pub data_type: LeakedDataType,
+    pub data: Arc<String>,
+    pub old_location: LeakLocation,
+    pub new_location: LeakLocation,
+}
+
+/// Locations of a piece of leaked data, in the old and new reports
+type LocationsInReports = (BTreeSet<LeakLocation>, BTreeSet<LeakLocation>);
+
+#[derive(Serialize)]
+struct JsonReportDiff<'d> {
+    version: ReportVersion,
+    #[serde(flatten)]
+    diff: &'d ReportDiff,
+}
+
+/// Compares the leaks of two reports. Leaks are identified by their type and
+/// value. When the same data is found at different locations in both reports,
+/// locations that changed are paired (in order) and reported as moved, and the
+/// remaining locations are reported as added or removed.
+pub fn diff_reports(old_leaks: Vec<ConfirmedLeak>, new_leaks: Vec<ConfirmedLeak>) -> ReportDiff {
+    let mut locations: BTreeMap<(LeakedDataType, Arc<String>), LocationsInReports> =
+        BTreeMap::new();
+    for leak in old_leaks {
+        locations
+            .entry((leak.data_type, leak.data))
+            .or_default()
+            .0
+            .insert(leak.location);
+    }
+    for leak in new_leaks {
+        locations
+            .entry((leak.data_type, leak.data))
+            .or_default()
+            .1
+            .insert(leak.location);
+    }
+
+    let mut diff = ReportDiff::default();
+    for ((data_type, data), (mut old_locations, mut new_locations)) in locations {
+        // Ignore unchanged locations
+        old_locations.retain(|location| !new_locations.remove(location));
+
+        let mut old_locations = old_locations.into_iter();
+        let mut new_locations = new_locations.into_iter();
+        loop {
+            match (old_locations.next(), new_locations.next()) {
+                (Some(old_location), Some(new_location)) => diff.moved.push(MovedLeak {
+                    data_type,
+                    data: data.clone(),
+                    old_location,
+                    new_location,
+                }),
+                (Some(location), None) => diff.removed.push(ConfirmedLeak {
+                    data_type,
+                    data: data.clone(),
+                    location,
+                }),
+                (None, Some(location)) => diff.added.push(ConfirmedLeak {
+                    data_type,
+                    data: data.clone(),
+                    location,
+                }),
+                (None, None) => break,
+            }
+        }
+    }
+
+    diff
+}
+
+/// Writes the differences between two reports, as text or as JSON.
+pub fn dump_report_diff<W: std::io::Write>(
+    mut writer: W,
+    diff: &ReportDiff,
+    json: bool,
+) -> Result<()> {
+    if json {
+        let report = JsonReportDiff {
+            version: ReportVersion {
+                executable: PKG_VERSION.into(),
+                format: REPORT_FORMAT_VERSION,
+            },
+            diff,
+        };
+        return Ok(serde_json::to_writer(writer, &report)?);
+    }
+
+    writeln!(writer, "Added leaks ({}):", diff.added.len())?;
+    for leak in &diff.added {
+        writeln!(writer, "  {}", display_confirmed_leak(leak))?;
+    }
+    writeln!(writer, "Removed leaks ({}):", diff.removed.len())?;
+    for leak in &diff.removed {
+        writeln!(writer, "  {}", display_confirmed_leak(leak))?;
+    }
+    writeln!(writer, "Moved leaks ({}):", diff.moved.len())?;
+    for leak in &diff.moved {
+        writeln!(
+            writer,
+            "  \"{}\" ({}) moved from {} to {}",
+            leak.data,
+            display_leaked_data_type(leak.data_type),
+            display_leak_location(&leak.old_location),
+            display_leak_location(&leak.new_location),
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_added_removed_and_moved_leaks() {
+        let diff = diff_reports(
+            vec![
+                ConfirmedLeak::new(
+                    LeakedDataType::StringLiteral,
+                    "unchanged",
+                    "main.cc",
+                    1,
+                    "a.out",
+                    0x10,
+                ),
+                ConfirmedLeak::new(
+                    LeakedDataType::StringLiteral,
+                    "removed",
+                    "main.cc",
+                    2,
+                    "a.out",
+                    0x20,
+                ),
+                ConfirmedLeak::new(
+                    LeakedDataType::StringLiteral,
+                    "moved",
+                    "main.cc",
+                    3,
+                    "a.out",
+                    0x30,
+                ),
+                ConfirmedLeak::new(
+                    LeakedDataType::StringLiteral,
+                    "duplicated",
+                    "main.cc",
+                    4,
+                    "a.out",
+                    0x40,
+                ),
+            ],
+            vec![
+                ConfirmedLeak::new(
+                    LeakedDataType::StringLiteral,
+                    "unchanged",
+                    "main.cc",
+                    1,
+                    "a.out",
+                    0x10,
+                ),
+                ConfirmedLeak::new(
+                    LeakedDataType::StringLiteral,
+                    "added",
+                    "main.cc",
+                    5,
+                    "a.out",
+                    0x50,
+                ),
+                ConfirmedLeak::new(
+                    LeakedDataType::StringLiteral,
+                    "moved",
+                    "main.cc",
+                    3,
+                    "a.out",
+                    0x38,
+                ),
+                ConfirmedLeak::new(
+                    LeakedDataType::StringLiteral,
+                    "duplicated",
+                    "main.cc",
+                    4,
+                    "a.out",
+                    0x40,
+                ),
+                ConfirmedLeak::new(
+                    LeakedDataType::StringLiteral,
+                    "duplicated",
+                    "main.cc",
+                    6,
+                    "a.out",
+                    0x60,
+                ),
+            ],
+        );
+
+        let added: Vec<_> = diff.added.iter().map(|leak| leak.data.as_str()).collect();
+        assert_eq!(added, vec!["added", "duplicated"]);
+        assert_eq!(diff.added[1].location.source.line, 6);
+        let removed: Vec<_> = diff.removed.iter().map(|leak| leak.data.as_str()).collect();
+        assert_eq!(removed, vec!["removed"]);
+        assert_eq!(diff.moved.len(), 1);
+        assert_eq!(*diff.moved[0].data, "moved");
+        assert_eq!(diff.moved[0].old_location.binary.offset, 0x30);
+        assert_eq!(diff.moved[0].new_location.binary.offset, 0x38);
+    }
+
+    #[test]
+    fn dump_report_diff_as_text() {
+        let diff = diff_reports(
+            vec![ConfirmedLeak::new(
+                LeakedDataType::StringLiteral,
+                "moved",
+                "main.cc",
+                3,
+                "a.out",
+                0x30,
+            )],
+            vec![ConfirmedLeak::new(
+                LeakedDataType::StringLiteral,
+                "moved",
+                "main.cc",
+                3,
+                "a.out",
+                0x38,
+            )],
+        );
+
+        let mut output = vec![];
+        dump_report_diff(&mut output, &diff, false).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "Added leaks (0):\n\
+             Removed leaks (0):\n\
+             Moved leaks (1):\n  \
+             \"moved\" (string literal) moved from offset 0x30 in \"a.out\" [declared at main.cc:3] \
+             to offset 0x38 in \"a.out\" [declared at main.cc:3]\n"
+        );
+    }
+
+    #[test]
+    fn dump_report_diff_as_json() {
+        let diff = diff_reports(
+            vec![],
+            vec![ConfirmedLeak::new(
+                LeakedDataType::StringLiteral,
+                "added",
+                "main.cc",
+                1,
+                "a.out",
+                0x10,
+            )],
+        );
+
+        let mut output = vec![];
+        dump_report_diff(&mut output, &diff, true).unwrap();
+        let output: serde_json::Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(output["version"]["format"], REPORT_FORMAT_VERSION);
+        assert_eq!(output["added"][0]["data"], "added");
+        assert_eq!(output["removed"].as_array().unwrap().len(), 0);
+        assert_eq!(output["moved"].as_array().unwrap().len(), 0);
+    }
+}
@@ -0,0 +1,350 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write as _,
+    ops::Range,
+    path::PathBuf,
+    sync::Arc,
+};
+
+use anyhow::Result;
+
+use super::{
+    binary_location_details, display_ascii_byte, display_leaked_data_type, escape_markup,
+    hex_dump_lines, BinaryFileCache, SourceFileCache, HEX_DUMP_LINE_SIZE, PKG_VERSION,
+};
+use crate::information_leak::ConfirmedLeak;
+
+/// Number of lines displayed before and after the line where leaked data is
+/// declared
+const SOURCE_CONTEXT_LINES: u64 = 2;
+/// Number of bytes displayed before and after leaked data in hex dumps
+const HEX_DUMP_CONTEXT_SIZE: u64 = 32;
+
+const STYLE: &str = r#"
+body { font-family: sans-serif; margin: 2em; color: #222; }
+h2 { font-size: 1.1em; margin-top: 2em; }
+table { border-collapse: collapse; width: 100%; }
+th, td { border: 1px solid #ccc; padding: 0.3em 0.6em; text-align: left; vertical-align: top; }
+th[data-sort] { cursor: pointer; background: #f0f0f0; }
+th[data-order="asc"]::after { content: " \25B2"; }
+th[data-order="desc"]::after { content: " \25BC"; }
+pre { margin: 0.5em 0; padding: 0.5em; background: #f7f7f7; overflow-x: auto; }
+mark { background: #ffd54f; }
+.filters label { margin-right: 1em; }
+"#;
+
+const SCRIPT: &str = r#"
+function applyFilters() {
+  const enabledTypes = new Set(
+    Array.from(document.querySelectorAll("input.type-filter:checked"), (input) => input.value)
+  );
+  for (const row of document.querySelectorAll("tr.leak")) {
+    row.hidden = !enabledTypes.has(row.dataset.type);
+  }
+  for (const group of document.querySelectorAll("section.group")) {
+    group.hidden = group.querySelector("tr.leak:not([hidden])") === null;
+  }
+}
+
+function sortTable(header) {
+  const body = header.closest("table").tBodies[0];
+  const index = header.cellIndex;
+  const numeric = header.dataset.sort === "number";
+  const ascending = header.dataset.order !== "asc";
+  for (const other of header.parentElement.children) {
+    delete other.dataset.order;
+  }
+  header.dataset.order = ascending ? "asc" : "desc";
+  const rows = Array.from(body.rows).sort((a, b) => {
+    const x = a.cells[index].dataset.value;
+    const y = b.cells[index].dataset.value;
+    const order = numeric ? Number(x) - Number(y) : x.localeCompare(y);
+    return ascending ? order : -order;
+  });
+  for (const row of rows) {
+    body.appendChild(row);
+  }
+}
+
+for (const input of document.querySelectorAll("input.type-filter")) {
+  input.addEventListener("change", applyFilters);
+}
+for (const header of document.querySelectorAll("th[data-sort]")) {
+  header.addEventListener("click", () => sortTable(header));
+}
+"#;
+
+/// Generates a self-contained HTML report. Leaks are grouped by source file
+/// and binary file, and can be filtered and sorted by data type. Source code
+/// around the declaration of the leaked data and a hex dump of the binary file
+/// around the leaked data are included when the files can be read.
+pub fn dump_confirmed_leaks_as_html<W, SortedConfirmedLeak>(
+    mut writer: W,
+    confirmed_leaks: Vec<SortedConfirmedLeak>,
+) -> Result<()>
+where
+    W: std::io::Write,
+    SortedConfirmedLeak: Into<ConfirmedLeak>,
+{
+    // Group leaks by source file and binary file
+    let mut leak_groups: BTreeMap<(PathBuf, Arc<PathBuf>), Vec<ConfirmedLeak>> = BTreeMap::new();
+    let mut data_types = BTreeSet::new();
+    let mut leak_count = 0;
+    for leak in confirmed_leaks {
+        let leak: ConfirmedLeak = leak.into();
+        data_types.insert(display_leaked_data_type(leak.data_type));
+        leak_groups
+            .entry((
+                leak.location.source.file.clone(),
+                leak.location.binary.file.clone(),
+            ))
+            .or_default()
+            .push(leak);
+        leak_count += 1;
+    }
+
+    writeln!(writer, "<!DOCTYPE html>")?;
+    writeln!(writer, "<html lang=\"en\">")?;
+    writeln!(writer, "<head>")?;
+    writeln!(writer, "<meta charset=\"utf-8\">")?;
+    writeln!(writer, "<title>Cpplumber report</title>")?;
+    writeln!(writer, "<style>{}</style>", STYLE)?;
+    writeln!(writer, "</head>")?;
+    writeln!(writer, "<body>")?;
+    writeln!(writer, "<h1>Cpplumber report</h1>")?;
+    writeln!(
+        writer,
+        "<p>{} leak(s) found, generated by cpplumber {}.</p>",
+        leak_count, PKG_VERSION
+    )?;
+
+    // Data type filters
+    writeln!(writer, "<div class=\"filters\">Show:")?;
+    for data_type in &data_types {
+        writeln!(
+            writer,
+            "<label><input type=\"checkbox\" class=\"type-filter\" value=\"{0}\" checked> {0}</label>",
+            escape_markup(data_type)
+        )?;
+    }
+    writeln!(writer, "</div>")?;
+
+    let mut source_files = SourceFileCache::default();
+    let mut binary_files = BinaryFileCache::default();
+    for ((source_file_path, binary_file_path), leaks) in leak_groups {
+        writeln!(writer, "<section class=\"group\">")?;
+        writeln!(
+            writer,
+            "<h2>Declared in <code>{}</code>, found in <code>{}</code></h2>",
+            escape_markup(&source_file_path.display().to_string()),
+            escape_markup(&binary_file_path.display().to_string())
+        )?;
+        writeln!(writer, "<table>")?;
+        writeln!(
+            writer,
+            "<thead><tr><th data-sort=\"text\">Value</th><th data-sort=\"text\">Type</th>\
+             <th data-sort=\"number\">Line</th><th data-sort=\"number\">Offset</th>\
+             <th>Details</th></tr></thead>"
+        )?;
+        writeln!(writer, "<tbody>")?;
+        let source_lines = source_files.get(&source_file_path);
+        let binary_data = binary_files.get(&binary_file_path);
+        for leak in leaks {
+            let data_type = escape_markup(&display_leaked_data_type(leak.data_type));
+            let value = escape_markup(&leak.data);
+            let source_line = leak.location.source.line;
+            let binary_offset = leak.location.binary.offset;
+
+            writeln!(writer, "<tr class=\"leak\" data-type=\"{}\">", data_type)?;
+            writeln!(
+                writer,
+                "<td data-value=\"{0}\"><code>{0}</code></td>",
+                value
+            )?;
+            writeln!(writer, "<td data-value=\"{0}\">{0}</td>", data_type)?;
+            writeln!(
+                writer,
+                "<td data-value=\"{0}\">{0}</td>",
+                leak.location.source.line
+            )?;
+            writeln!(
+                writer,
+                "<td data-value=\"{0}\">0x{0:x}</td>",
+                leak.location.binary.offset
+            )?;
+            writeln!(writer, "<td>")?;
+            writeln!(
+                writer,
+                "{}",
+                escape_markup(&binary_location_details(&leak.location.binary).join(", "))
+            )?;
+            writeln!(writer, "<details><summary>Context</summary>")?;
+            match source_lines {
+                Some(lines) => writeln!(
+                    writer,
+                    "<pre class=\"source\">{}</pre>",
+                    source_snippet(lines, source_line)
+                )?,
+                None => writeln!(writer, "<p>Source file unavailable.</p>")?,
+            }
+            match binary_data {
+                Some(data) => writeln!(
+                    writer,
+                    "<pre class=\"hex\">{}</pre>",
+                    hex_dump(
+                        data,
+                        binary_offset..binary_offset + leak.location.binary.size
+                    )
+                )?,
+                None => writeln!(writer, "<p>Binary file unavailable.</p>")?,
+            }
+            writeln!(writer, "</details>")?;
+            writeln!(writer, "</td>")?;
+            writeln!(writer, "</tr>")?;
+        }
+        writeln!(writer, "</tbody>")?;
+        writeln!(writer, "</table>")?;
+        writeln!(writer, "</section>")?;
+    }
+
+    writeln!(writer, "<script>{}</script>", SCRIPT)?;
+    writeln!(writer, "</body>")?;
+    writeln!(writer, "</html>")?;
+
+    Ok(())
+}
+
+/// Returns the lines surrounding the given line (1-based) as HTML, with line
+/// numbers. The given line is highlighted.
+fn source_snippet(lines: &[String], line: u64) -> String {
+    let first_line = line.saturating_sub(SOURCE_CONTEXT_LINES).max(1);
+    let last_line = (line + SOURCE_CONTEXT_LINES).min(lines.len() as u64);
+
+    let mut snippet = String::new();
+    for line_number in first_line..=last_line {
+        let content = escape_markup(&lines[line_number as usize - 1]);
+        if line_number == line {
+            let _ = writeln!(snippet, "<mark>{:>5} | {}</mark>", line_number, content);
+        } else {
+            let _ = writeln!(snippet, "{:>5} | {}", line_number, content);
+        }
+    }
+
+    snippet
+}
+
+/// Returns a hex dump of `data` around `highlighted_range` as HTML. Bytes in
+/// `highlighted_range` are highlighted.
+fn hex_dump(data: &[u8], highlighted_range: Range<u64>) -> String {
+    let mut dump = String::new();
+    for line_range in hex_dump_lines(data.len() as u64, &highlighted_range, HEX_DUMP_CONTEXT_SIZE) {
+        let mut hex = String::new();
+        let mut ascii = String::new();
+        for offset in line_range.clone() {
+            let byte = data[offset as usize];
+            let byte_hex = format!("{:02x}", byte);
+            let byte_ascii = escape_markup(&display_ascii_byte(byte).to_string());
+            if highlighted_range.contains(&offset) {
+                let _ = write!(hex, "<mark>{}</mark> ", byte_hex);
+                let _ = write!(ascii, "<mark>{}</mark>", byte_ascii);
+            } else {
+                let _ = write!(hex, "{} ", byte_hex);
+                ascii.push_str(&byte_ascii);
+            }
+        }
+        // Pad incomplete lines so that the ASCII column stays aligned
+        for _ in line_range.end..line_range.start + HEX_DUMP_LINE_SIZE {
+            hex.push_str("   ");
+        }
+        let _ = writeln!(dump, "{:08x}  {} |{}|", line_range.start, hex, ascii);
+    }
+
+    dump
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+    use crate::information_leak::LeakedDataType;
+
+    #[test]
+    fn html_report_contains_context() {
+        let mut source_file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(
+            source_file,
+            "#include <cstdio>\n\nint main() {{\n  puts(\"<secret>\");\n}}"
+        )
+        .unwrap();
+        let mut binary_file = tempfile::NamedTempFile::new().unwrap();
+        binary_file.write_all(&[0u8; 0x20]).unwrap();
+        binary_file.write_all(b"<secret>\0").unwrap();
+
+        let mut leaks = vec![
+            ConfirmedLeak::new(
+                LeakedDataType::StringLiteral,
+                "<secret>",
+                source_file.path(),
+                4,
+                binary_file.path(),
+                0x20,
+            ),
+            ConfirmedLeak::new(
+                LeakedDataType::ClassName,
+                "MyClass",
+                "missing.cc",
+                1,
+                "missing.exe",
+                0x0,
+            ),
+        ];
+        for leak in &mut leaks {
+            leak.location.binary.section = Some(Arc::new(".rodata".to_string()));
+        }
+
+        let mut report = vec![];
+        dump_confirmed_leaks_as_html(&mut report, leaks).unwrap();
+        let report = String::from_utf8(report).unwrap();
+
+        assert!(report.starts_with("<!DOCTYPE html>"));
+        assert!(report.contains("2 leak(s) found"));
+        // Filters
+        assert!(report.contains("value=\"string literal\" checked"));
+        assert!(report.contains("value=\"class name\" checked"));
+        // Escaped value
+        assert!(report.contains("<code>&lt;secret&gt;</code>"));
+        // Source snippet
+        assert!(report.contains("<mark>    4 |   puts(&quot;&lt;secret&gt;&quot;);</mark>"));
+        assert!(report.contains("    2 | \n"));
+        // Hex dump
+        assert!(report.contains("00000020  <mark>3c</mark> <mark>73</mark>"));
+        // Missing files
+        assert!(report.contains("Source file unavailable."));
+        assert!(report.contains("Binary file unavailable."));
+    }
+
+    #[test]
+    fn hex_dump_highlights_range() {
+        let data: Vec<u8> = (0x40..0x80).collect();
+        assert_eq!(
+            hex_dump(&data, 0x3e..0x40),
+            "00000010  50 51 52 53 54 55 56 57 58 59 5a 5b 5c 5d 5e 5f  |PQRSTUVWXYZ[\\]^_|\n\
+             00000020  60 61 62 63 64 65 66 67 68 69 6a 6b 6c 6d 6e 6f  |`abcdefghijklmno|\n\
+             00000030  70 71 72 73 74 75 76 77 78 79 7a 7b 7c 7d <mark>7e</mark> <mark>7f</mark>  |pqrstuvwxyz{|}<mark>~</mark><mark>.</mark>|\n"
+        );
+    }
+
+    #[test]
+    fn source_snippet_at_file_boundaries() {
+        let lines: Vec<String> = ["a", "b", "c"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(
+            source_snippet(&lines, 1),
+            "<mark>    1 | a</mark>\n    2 | b\n    3 | c\n"
+        );
+        assert_eq!(
+            source_snippet(&lines, 3),
+            "    1 | a\n    2 | b\n<mark>    3 | c</mark>\n"
+        );
+    }
+}
@@ -0,0 +1,168 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use anyhow::Result;
+
+use super::{
+    display_binary_location_details, display_confirmed_leak, display_leaked_data_type,
+    escape_markup,
+};
+use crate::information_leak::ConfirmedLeak;
+
+const TEST_SUITES_NAME: &str = "cpplumber";
+
+/// Generates a JUnit XML report. Each source file is reported as a test suite
+/// and each leak as a failed test case of the suite corresponding to the file
+/// the leaked data is declared in.
+pub fn dump_confirmed_leaks_as_junit<W, SortedConfirmedLeak>(
+    mut writer: W,
+    confirmed_leaks: Vec<SortedConfirmedLeak>,
+) -> Result<()>
+where
+    W: std::io::Write,
+    SortedConfirmedLeak: Into<ConfirmedLeak>,
+{
+    // Group leaks by source file, test suites are sorted by name
+    let mut leaks_per_source_file: BTreeMap<PathBuf, Vec<ConfirmedLeak>> = BTreeMap::new();
+    let mut leak_count = 0;
+    for leak in confirmed_leaks {
+        let leak: ConfirmedLeak = leak.into();
+        leaks_per_source_file
+            .entry(leak.location.source.file.clone())
+            .or_default()
+            .push(leak);
+        leak_count += 1;
+    }
+
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        writer,
+        r#"<testsuites name="{}" tests="{}" failures="{}">"#,
+        TEST_SUITES_NAME, leak_count, leak_count
+    )?;
+    for (source_file, leaks) in leaks_per_source_file {
+        let suite_name = escape_markup(&source_file.display().to_string());
+        writeln!(
+            writer,
+            r#"  <testsuite name="{}" tests="{}" failures="{}">"#,
+            suite_name,
+            leaks.len(),
+            leaks.len()
+        )?;
+        for leak in leaks {
+            writeln!(
+                writer,
+                r#"    <testcase name="{}" classname="{}">"#,
+                escape_markup(&format!(
+                    "\"{}\" ({}) declared at line {}",
+                    leak.data,
+                    display_leaked_data_type(leak.data_type),
+                    leak.location.source.line
+                )),
+                suite_name,
+            )?;
+            writeln!(
+                writer,
+                r#"      <failure message="{}" type="{}">{}</failure>"#,
+                escape_markup(&format!(
+                    "Leaked at offset 0x{:x}{} in \"{}\"",
+                    leak.location.binary.offset,
+                    display_binary_location_details(&leak.location.binary),
+                    leak.location.binary.file.display(),
+                )),
+                escape_markup(&display_leaked_data_type(leak.data_type)),
+                escape_markup(&display_confirmed_leak(&leak)),
+            )?;
+            writeln!(writer, "    </testcase>")?;
+        }
+        writeln!(writer, "  </testsuite>")?;
+    }
+    writeln!(writer, "</testsuites>")?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::information_leak::LeakedDataType;
+
+    #[test]
+    fn junit_report_groups_leaks_by_source_file() {
+        let mut leaks = vec![
+            ConfirmedLeak::new(
+                LeakedDataType::StringLiteral,
+                "<secret> & \"co\"",
+                "main.cc",
+                3,
+                "a.out",
+                0x10,
+            ),
+            ConfirmedLeak::new(
+                LeakedDataType::StringLiteral,
+                "line\nbreak\u{1}",
+                "utils.h",
+                3,
+                "a.out",
+                0x20,
+            ),
+            ConfirmedLeak::new(
+                LeakedDataType::StringLiteral,
+                "other",
+                "main.cc",
+                3,
+                "a.out",
+                0x30,
+            ),
+        ];
+        for leak in &mut leaks {
+            leak.location.binary.section = Some(Arc::new(".rodata".to_string()));
+        }
+
+        let mut report = vec![];
+        dump_confirmed_leaks_as_junit(&mut report, leaks).unwrap();
+        let report = String::from_utf8(report).unwrap();
+
+        let document = roxmltree::Document::parse(&report).expect("invalid XML");
+        let test_suites = document.root_element();
+        assert_eq!(test_suites.tag_name().name(), "testsuites");
+        assert_eq!(test_suites.attribute("tests"), Some("3"));
+        assert_eq!(test_suites.attribute("failures"), Some("3"));
+
+        let suites: Vec<_> = test_suites
+            .children()
+            .filter(|node| node.has_tag_name("testsuite"))
+            .collect();
+        assert_eq!(suites.len(), 2);
+        assert_eq!(suites[0].attribute("name"), Some("main.cc"));
+        assert_eq!(suites[0].attribute("tests"), Some("2"));
+        assert_eq!(suites[1].attribute("name"), Some("utils.h"));
+
+        let test_case = suites[0]
+            .children()
+            .find(|node| node.has_tag_name("testcase"))
+            .unwrap();
+        assert_eq!(
+            test_case.attribute("name"),
+            Some("\"<secret> & \"co\"\" (string literal) declared at line 3")
+        );
+        let failure = test_case
+            .children()
+            .find(|node| node.has_tag_name("failure"))
+            .unwrap();
+        assert_eq!(
+            failure.attribute("message"),
+            Some("Leaked at offset 0x10 (section \".rodata\") in \"a.out\"")
+        );
+
+        let test_case = suites[1]
+            .children()
+            .find(|node| node.has_tag_name("testcase"))
+            .unwrap();
+        assert_eq!(
+            test_case.attribute("name"),
+            Some("\"line\nbreak\\u{1}\" (string literal) declared at line 3")
+        );
+    }
+}
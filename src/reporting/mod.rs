@@ -0,0 +1,882 @@
+mod diff;
+mod html;
+mod junit;
+mod sarif;
+mod summary;
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Write as _,
+    fs,
+    ops::Range,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    binary_format::BinaryFileData,
+    information_leak::{
+        BinaryLocation, ColumnRange, ConfirmedLeak, CxxAbi, LeakLocation, LeakedDataForm,
+        LeakedDataType, SourceLocation,
+    },
+};
+
+pub use diff::{diff_reports, dump_report_diff, MovedLeak, ReportDiff};
+pub use html::dump_confirmed_leaks_as_html;
+pub use junit::dump_confirmed_leaks_as_junit;
+pub use sarif::dump_confirmed_leaks_as_sarif;
+use summary::dump_summary_as_text;
+pub use summary::{ReportSummary, TopOffender};
+
+const PKG_VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Version of the JSON reports' format. Must be incremented when the format of
+/// `JsonReport` (or of the types it contains) changes.
+///
+/// - 2: leak sizes, forms, columns, binary layout information, context and
+///   summary
+const REPORT_FORMAT_VERSION: u32 = 2;
+/// Oldest format version that can still be read (fields added since then have
+/// default values)
+const MIN_REPORT_FORMAT_VERSION: u32 = 1;
+/// Number of bytes displayed on each line of hex dumps
+const HEX_DUMP_LINE_SIZE: u64 = 16;
+
+/// Names of the available report formats, as expected on the command line
+pub const REPORT_FORMAT_NAMES: &[&str] = &["text", "json", "sarif", "junit", "html"];
+
+/// Describes the format of the generated reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReportFormat {
+    /// Human-readable text, one leak per line
+    #[default]
+    Text,
+    /// JSON document, versioned with `ReportVersion`
+    Json,
+    /// SARIF 2.1.0 log, for tools that consume static analysis results
+    Sarif,
+    /// JUnit XML, for CI test dashboards
+    JUnit,
+    /// Self-contained HTML page, for browsing results
+    Html,
+}
+
+impl FromStr for ReportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            "sarif" => Ok(Self::Sarif),
+            "junit" => Ok(Self::JUnit),
+            "html" => Ok(Self::Html),
+            _ => Err(anyhow!("Unknown report format '{}'", s)),
+        }
+    }
+}
+
+/// Names of the available leak groupings, as expected on the command line
+pub const LEAK_GROUPING_NAMES: &[&str] = &["file", "binary", "type", "value"];
+
+/// Describes how leaks are grouped in text reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeakGrouping {
+    /// Group leaks by the source file the leaked data is declared in
+    File,
+    /// Group leaks by the binary file the leaked data is found in
+    Binary,
+    /// Group leaks by type of data
+    Type,
+    /// Group leaks by leaked value
+    Value,
+}
+
+impl FromStr for LeakGrouping {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "file" => Ok(Self::File),
+            "binary" => Ok(Self::Binary),
+            "type" => Ok(Self::Type),
+            "value" => Ok(Self::Value),
+            _ => Err(anyhow!("Unknown leak grouping '{}'", s)),
+        }
+    }
+}
+
+/// Options controlling the content of the generated reports
+#[derive(Debug, Clone, Default)]
+pub struct ReportOptions {
+    /// Format of the report
+    pub format: ReportFormat,
+    /// Print the line of source code declaring the leaked data, with the
+    /// declaration underlined (text reports only)
+    pub show_source: bool,
+    /// Number of bytes of the binary file to include before and after leaked
+    /// data, as a hex dump in text reports and hex-encoded in JSON reports (no
+    /// context is included if 0)
+    pub context_size: u64,
+    /// Group leaks under headers (text reports only)
+    pub group_by: Option<LeakGrouping>,
+    /// Append a summary of the leaks (text reports only, JSON reports always
+    /// contain a summary)
+    pub show_summary: bool,
+}
+
+#[derive(Serialize)]
+struct JsonReport {
+    version: ReportVersion,
+    summary: ReportSummary,
+    leaks: Vec<JsonLeak>,
+}
+
+#[derive(Serialize)]
+struct JsonLeak {
+    #[serde(flatten)]
+    leak: ConfirmedLeak,
+    /// Bytes surrounding the leaked data in the binary file, if requested
+    #[serde(skip_serializing_if = "Option::is_none")]
+    context: Option<BinaryContext>,
+}
+
+/// Bytes of a binary file, around leaked data
+#[derive(Serialize)]
+struct BinaryContext {
+    /// Offset of the first byte in the binary file
+    offset: u64,
+    /// Hex-encoded bytes
+    data: String,
+}
+
+/// Leaks of a JSON report, as read by `read_json_report` (the version is
+/// checked beforehand with `JsonReportHeader`)
+#[derive(Deserialize)]
+struct JsonReportContent {
+    leaks: Vec<ConfirmedLeak>,
+}
+
+/// Only contains the report's version, to be able to check it before parsing
+/// the rest of the file.
+#[derive(Deserialize)]
+struct JsonReportHeader {
+    version: ReportVersion,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ReportVersion {
+    executable: String,
+    format: u32,
+}
+
+pub fn dump_confirmed_leaks<W, SortedConfirmedLeak>(
+    writer: W,
+    confirmed_leaks: Vec<SortedConfirmedLeak>,
+    options: &ReportOptions,
+) -> Result<()>
+where
+    W: std::io::Write,
+    SortedConfirmedLeak: Into<ConfirmedLeak> + Ord + Eq + Serialize,
+{
+    match options.format {
+        ReportFormat::Text => dump_confirmed_leaks_as_text(writer, confirmed_leaks, options),
+        ReportFormat::Json => dump_confirmed_leaks_as_json(writer, confirmed_leaks, options),
+        ReportFormat::Sarif => dump_confirmed_leaks_as_sarif(writer, confirmed_leaks),
+        ReportFormat::JUnit => dump_confirmed_leaks_as_junit(writer, confirmed_leaks),
+        ReportFormat::Html => dump_confirmed_leaks_as_html(writer, confirmed_leaks),
+    }
+}
+
+/// Loads the leaks contained in a report generated with `ReportFormat::Json`.
+pub fn read_json_report(file_path: &Path) -> Result<Vec<ConfirmedLeak>> {
+    let report_data = fs::read(file_path)?;
+    let header: JsonReportHeader =
+        serde_json::from_slice(&report_data).with_context(|| "Invalid JSON report")?;
+    if !(MIN_REPORT_FORMAT_VERSION..=REPORT_FORMAT_VERSION).contains(&header.version.format) {
+        return Err(anyhow!(
+            "Unsupported report format version {} (expected {} to {}, generated by cpplumber {})",
+            header.version.format,
+            MIN_REPORT_FORMAT_VERSION,
+            REPORT_FORMAT_VERSION,
+            header.version.executable
+        ));
+    }
+
+    let report: JsonReportContent =
+        serde_json::from_slice(&report_data).with_context(|| "Invalid JSON report")?;
+
+    Ok(report.leaks)
+}
+
+fn dump_confirmed_leaks_as_json<W, SortedConfirmedLeak>(
+    writer: W,
+    confirmed_leaks: Vec<SortedConfirmedLeak>,
+    options: &ReportOptions,
+) -> Result<()>
+where
+    W: std::io::Write,
+    SortedConfirmedLeak: Into<ConfirmedLeak> + Ord + Eq + Serialize,
+{
+    let leaks: Vec<ConfirmedLeak> = confirmed_leaks.into_iter().map(Into::into).collect();
+    let summary = leaks.iter().collect();
+
+    let mut binary_files = BinaryFileCache::default();
+    let leaks = leaks
+        .into_iter()
+        .map(|leak| {
+            let context = if options.context_size > 0 {
+                binary_files
+                    .get(&leak.location.binary.file)
+                    .map(|data| binary_context(data, &leak.location.binary, options.context_size))
+            } else {
+                None
+            };
+            JsonLeak { leak, context }
+        })
+        .collect();
+    let report = JsonReport {
+        version: ReportVersion {
+            executable: PKG_VERSION.into(),
+            format: REPORT_FORMAT_VERSION,
+        },
+        summary,
+        leaks,
+    };
+
+    Ok(serde_json::to_writer(writer, &report)?)
+}
+
+fn dump_confirmed_leaks_as_text<W, SortedConfirmedLeak>(
+    mut writer: W,
+    confirmed_leaks: Vec<SortedConfirmedLeak>,
+    options: &ReportOptions,
+) -> Result<()>
+where
+    W: std::io::Write,
+    SortedConfirmedLeak: Into<ConfirmedLeak> + Ord + Eq + Serialize,
+{
+    let leaks: Vec<ConfirmedLeak> = confirmed_leaks.into_iter().map(Into::into).collect();
+    let mut source_files = SourceFileCache::default();
+    let mut binary_files = BinaryFileCache::default();
+    match options.group_by {
+        None => {
+            for leak in &leaks {
+                let text = display_confirmed_leak_with_context(
+                    leak,
+                    options,
+                    &mut source_files,
+                    &mut binary_files,
+                );
+                write!(&mut writer, "{}", text)?;
+            }
+        }
+        Some(grouping) => {
+            let mut leak_groups: BTreeMap<String, Vec<&ConfirmedLeak>> = BTreeMap::new();
+            for leak in &leaks {
+                let group = match grouping {
+                    LeakGrouping::File => leak.location.source.file.display().to_string(),
+                    LeakGrouping::Binary => leak.location.binary.file.display().to_string(),
+                    LeakGrouping::Type => display_leaked_data_type(leak.data_type),
+                    LeakGrouping::Value => format!("\"{}\"", leak.data),
+                };
+                leak_groups.entry(group).or_default().push(leak);
+            }
+            for (group, group_leaks) in leak_groups {
+                writeln!(&mut writer, "{} ({} leak(s)):", group, group_leaks.len())?;
+                for leak in group_leaks {
+                    let text = display_confirmed_leak_with_context(
+                        leak,
+                        options,
+                        &mut source_files,
+                        &mut binary_files,
+                    );
+                    for line in text.lines() {
+                        writeln!(&mut writer, "  {}", line)?;
+                    }
+                }
+            }
+        }
+    }
+
+    if options.show_summary {
+        dump_summary_as_text(&mut writer, &leaks.iter().collect())?;
+    }
+
+    Ok(())
+}
+
+/// Returns a text representation of `ConfirmedLeak`, followed by the context
+/// requested in `options` (i.e., source code and bytes around the leaked data)
+fn display_confirmed_leak_with_context(
+    leak: &ConfirmedLeak,
+    options: &ReportOptions,
+    source_files: &mut SourceFileCache,
+    binary_files: &mut BinaryFileCache,
+) -> String {
+    let mut text = display_confirmed_leak(leak);
+    text.push('\n');
+    if options.show_source {
+        let source = &leak.location.source;
+        if let Some(lines) = source_files.get(&source.file) {
+            if let Some(snippet) = underlined_source_line(lines, source.line, source.columns) {
+                text.push_str(&snippet);
+            }
+        }
+    }
+    if options.context_size > 0 {
+        if let Some(data) = binary_files.get(&leak.location.binary.file) {
+            text.push_str(&underlined_hex_dump(
+                data,
+                leaked_range(&leak.location.binary),
+                options.context_size,
+            ));
+        }
+    }
+
+    text
+}
+
+/// Returns a one-line text representation of `ConfirmedLeak`
+fn display_confirmed_leak(leak: &ConfirmedLeak) -> String {
+    format!(
+        "\"{}\" ({}) leaked at {}",
+        leak.data,
+        display_leaked_data_type(leak.data_type),
+        display_leak_location(&leak.location),
+    )
+}
+
+/// Returns a one-line text representation of `LeakLocation`
+fn display_leak_location(location: &LeakLocation) -> String {
+    format!(
+        "offset 0x{:x}{} in \"{}\" [declared at {}]",
+        location.binary.offset,
+        display_binary_location_details(&location.binary),
+        location.binary.file.display(),
+        display_source_location(&location.source),
+    )
+}
+
+/// Returns a text representation of `SourceLocation` (i.e., `file:line` or
+/// `file:line:column` if the column is known)
+fn display_source_location(location: &SourceLocation) -> String {
+    match location.columns {
+        Some(columns) => format!(
+            "{}:{}:{}",
+            location.file.display(),
+            location.line,
+            columns.start
+        ),
+        None => format!("{}:{}", location.file.display(), location.line),
+    }
+}
+
+/// Returns the given line (1-based) of `lines` prefixed with its line number,
+/// followed by a line underlining `columns` with carets (if known). Returns
+/// `None` if the line doesn't exist.
+fn underlined_source_line(
+    lines: &[String],
+    line: u64,
+    columns: Option<ColumnRange>,
+) -> Option<String> {
+    let content = lines.get((line as usize).checked_sub(1)?)?;
+    let mut snippet = format!("{:>5} | {}\n", line, content);
+    if let Some(columns) = columns {
+        // Columns are byte offsets, convert them to chars to align carets
+        let start = (columns.start as usize).saturating_sub(1);
+        let end = (columns.end as usize).saturating_sub(1).min(content.len());
+        if let (Some(prefix), Some(underlined)) = (content.get(..start), content.get(start..end)) {
+            // Keep tabs to stay aligned with the line above
+            let padding: String = prefix
+                .chars()
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect();
+            let carets = "^".repeat(underlined.chars().count().max(1));
+            let _ = writeln!(snippet, "      | {}{}", padding, carets);
+        }
+    }
+
+    Some(snippet)
+}
+
+/// Returns a text representation of the form of the leaked data and of the
+/// section-related information contained in `BinaryLocation` (empty if the
+/// data is found in its plain form, outside of known sections)
+fn display_binary_location_details(location: &BinaryLocation) -> String {
+    let details = binary_location_details(location);
+    if details.is_empty() {
+        String::new()
+    } else {
+        format!(" ({})", details.join(", "))
+    }
+}
+
+/// Returns text representations of the form of the leaked data and of each
+/// piece of section-related information contained in `BinaryLocation`
+fn binary_location_details(location: &BinaryLocation) -> Vec<String> {
+    let mut details = vec![];
+    if let Some(form) = display_leaked_data_form(&location.form) {
+        details.push(form);
+    }
+    if let Some(architecture) = &location.architecture {
+        details.push(format!("architecture \"{}\"", architecture));
+    }
+    if let Some(archive_member) = &location.archive_member {
+        details.push(format!("member \"{}\"", archive_member));
+    }
+    if let Some(section) = &location.section {
+        details.push(format!("section \"{}\"", section));
+    }
+    if let Some(segment) = &location.segment {
+        details.push(format!("segment \"{}\"", segment));
+    }
+    if let Some(relative_virtual_address) = location.relative_virtual_address {
+        details.push(format!("RVA 0x{:x}", relative_virtual_address));
+    }
+    if let Some(virtual_address) = location.virtual_address {
+        details.push(format!("VA 0x{:x}", virtual_address));
+    }
+    if let Some(data_directory) = &location.data_directory {
+        details.push(format!("in {}", data_directory));
+    }
+
+    details
+}
+
+/// Returns the range of offsets of leaked data in its binary file
+fn leaked_range(location: &BinaryLocation) -> Range<u64> {
+    location.offset..location.offset + location.size
+}
+
+/// Returns the `context_size` bytes preceding and following leaked data in
+/// `data`, as well as the leaked data itself
+fn binary_context(data: &[u8], location: &BinaryLocation, context_size: u64) -> BinaryContext {
+    let data_size = data.len() as u64;
+    let leaked_range = leaked_range(location);
+    let start = leaked_range
+        .start
+        .saturating_sub(context_size)
+        .min(data_size);
+    let end = leaked_range.end.saturating_add(context_size).min(data_size);
+
+    let mut hex = String::with_capacity(2 * (end - start) as usize);
+    for byte in &data[start as usize..end as usize] {
+        let _ = write!(hex, "{:02x}", byte);
+    }
+
+    BinaryContext {
+        offset: start,
+        data: hex,
+    }
+}
+
+/// Returns the ranges of offsets displayed on each line of a hex dump of
+/// `data_size` bytes, showing `context_size` bytes before and after
+/// `highlighted_range`. Lines are aligned on `HEX_DUMP_LINE_SIZE`.
+fn hex_dump_lines(
+    data_size: u64,
+    highlighted_range: &Range<u64>,
+    context_size: u64,
+) -> Vec<Range<u64>> {
+    let start = highlighted_range.start.saturating_sub(context_size) / HEX_DUMP_LINE_SIZE
+        * HEX_DUMP_LINE_SIZE;
+    let end = highlighted_range
+        .end
+        .saturating_add(context_size)
+        .min(data_size);
+
+    (start..end)
+        .step_by(HEX_DUMP_LINE_SIZE as usize)
+        .map(|line_start| line_start..(line_start + HEX_DUMP_LINE_SIZE).min(end))
+        .collect()
+}
+
+/// Returns a hex dump of `data` around `highlighted_range`, with
+/// `context_size` bytes of context. Bytes in `highlighted_range` are
+/// underlined with carets.
+fn underlined_hex_dump(data: &[u8], highlighted_range: Range<u64>, context_size: u64) -> String {
+    let mut dump = String::new();
+    for line_range in hex_dump_lines(data.len() as u64, &highlighted_range, context_size) {
+        let mut hex = String::new();
+        let mut carets = String::new();
+        let mut ascii = String::new();
+        for offset in line_range.clone() {
+            let byte = data[offset as usize];
+            let _ = write!(hex, "{:02x} ", byte);
+            carets.push_str(if highlighted_range.contains(&offset) {
+                "^^ "
+            } else {
+                "   "
+            });
+            ascii.push(display_ascii_byte(byte));
+        }
+        // Pad incomplete lines so that the ASCII column stays aligned
+        for _ in line_range.end..line_range.start + HEX_DUMP_LINE_SIZE {
+            hex.push_str("   ");
+        }
+        let _ = writeln!(dump, "{:08x}  {} |{}|", line_range.start, hex, ascii);
+        if carets.contains('^') {
+            let _ = writeln!(dump, "          {}", carets.trim_end());
+        }
+    }
+
+    dump
+}
+
+/// Returns the character representing `byte` in the ASCII column of hex
+/// dumps
+fn display_ascii_byte(byte: u8) -> char {
+    if byte.is_ascii_graphic() || byte == b' ' {
+        byte as char
+    } else {
+        '.'
+    }
+}
+
+/// Source files' lines, loaded on demand
+#[derive(Default)]
+struct SourceFileCache(HashMap<PathBuf, Option<Vec<String>>>);
+
+impl SourceFileCache {
+    /// Returns the lines of the given file, or `None` if it cannot be read
+    fn get(&mut self, file_path: &Path) -> Option<&[String]> {
+        self.0
+            .entry(file_path.to_path_buf())
+            .or_insert_with(|| match std::fs::read(file_path) {
+                Ok(content) => Some(
+                    String::from_utf8_lossy(&content)
+                        .lines()
+                        .map(str::to_string)
+                        .collect(),
+                ),
+                Err(e) => {
+                    log::warn!("Failed to read '{}': {}", file_path.display(), e);
+                    None
+                }
+            })
+            .as_deref()
+    }
+}
+
+/// Binary files' content, mapped on demand
+#[derive(Default)]
+struct BinaryFileCache(HashMap<PathBuf, Option<BinaryFileData>>);
+
+impl BinaryFileCache {
+    /// Returns the content of the given file, or `None` if it cannot be read
+    fn get(&mut self, file_path: &Path) -> Option<&[u8]> {
+        self.0
+            .entry(file_path.to_path_buf())
+            .or_insert_with(|| match BinaryFileData::open(file_path) {
+                Ok(data) => Some(data),
+                Err(e) => {
+                    log::warn!("Failed to read '{}': {}", file_path.display(), e);
+                    None
+                }
+            })
+            .as_deref()
+    }
+}
+
+/// Escapes special characters for use in XML and HTML attribute values and
+/// text. Characters that aren't allowed in XML 1.0 documents (e.g., most
+/// control characters) are replaced with a Rust-like escape sequence.
+fn escape_markup(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Line breaks and tabs would be normalized in attribute values
+            '\t' | '\n' | '\r' => escaped.push_str(&format!("&#x{:x};", c as u32)),
+            '\u{0}'..='\u{1f}' | '\u{fffe}' | '\u{ffff}' => {
+                escaped.push_str(&c.escape_unicode().to_string())
+            }
+            _ => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Returns a text representation of `LeakedDataForm` (`None` for
+/// `LeakedDataForm::Plain`)
+fn display_leaked_data_form(form: &LeakedDataForm) -> Option<String> {
+    match form {
+        LeakedDataForm::Plain => None,
+        LeakedDataForm::MangledName { abi, name } => Some(format!(
+            "{} mangled name \"{}\"",
+            display_cxx_abi(*abi),
+            name
+        )),
+        LeakedDataForm::RttiName(name) => Some(format!("RTTI name \"{}\"", name)),
+    }
+}
+
+/// Returns a text representation of `CxxAbi`
+fn display_cxx_abi(abi: CxxAbi) -> &'static str {
+    match abi {
+        CxxAbi::Itanium => "Itanium",
+        CxxAbi::Msvc => "MSVC",
+    }
+}
+
+/// Returns a text representation of `LeakedDataType`
+fn display_leaked_data_type(data_type: LeakedDataType) -> String {
+    match data_type {
+        LeakedDataType::StringLiteral => "string literal".to_string(),
+        LeakedDataType::StructName => "struct name".to_string(),
+        LeakedDataType::ClassName => "class name".to_string(),
+        LeakedDataType::FunctionName => "function name".to_string(),
+        LeakedDataType::EnumName => "enum name".to_string(),
+        LeakedDataType::EnumConstantName => "enum constant name".to_string(),
+        LeakedDataType::VariableName => "variable name".to_string(),
+        LeakedDataType::NamespaceName => "namespace name".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::information_leak::ConfirmedLeakWithUniqueLocation;
+
+    #[test]
+    fn read_written_json_report() {
+        let mut leak = ConfirmedLeak::new(
+            LeakedDataType::StructName,
+            "MyStruct",
+            "main.cc",
+            7,
+            "a.out",
+            0x1234,
+        );
+        leak.location.binary.section = Some(Arc::new(".rodata".to_string()));
+        leak.location.binary.virtual_address = Some(0x401234);
+        let mut report_file = tempfile::NamedTempFile::new().unwrap();
+        dump_confirmed_leaks(
+            &mut report_file,
+            vec![ConfirmedLeakWithUniqueLocation::from(leak)],
+            &ReportOptions {
+                format: ReportFormat::Json,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let leaks = read_json_report(report_file.path()).unwrap();
+        assert_eq!(leaks.len(), 1);
+        assert_eq!(leaks[0].data_type, LeakedDataType::StructName);
+        assert_eq!(*leaks[0].data, "MyStruct");
+        assert_eq!(leaks[0].location.source.line, 7);
+        assert_eq!(leaks[0].location.binary.offset, 0x1234);
+        assert_eq!(leaks[0].location.binary.size, 8);
+        assert_eq!(leaks[0].location.binary.virtual_address, Some(0x401234));
+    }
+
+    #[test]
+    fn underlined_source_line_with_columns() {
+        let lines: Vec<String> = ["int main() {", "\tputs(\"sécret\");", "}"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(
+            underlined_source_line(&lines, 2, Some(ColumnRange { start: 7, end: 16 })).unwrap(),
+            "    2 | \tputs(\"sécret\");\n      | \t     ^^^^^^^^\n"
+        );
+        // Unknown columns
+        assert_eq!(
+            underlined_source_line(&lines, 1, None).unwrap(),
+            "    1 | int main() {\n"
+        );
+        // Out-of-range line
+        assert!(underlined_source_line(&lines, 4, None).is_none());
+    }
+
+    #[test]
+    fn json_report_contains_binary_context() {
+        let mut binary_file = tempfile::NamedTempFile::new().unwrap();
+        std::io::Write::write_all(&mut binary_file, b"\x01\x02secret\x00\x03").unwrap();
+        let leak = ConfirmedLeak::new(
+            LeakedDataType::StringLiteral,
+            "secret",
+            "main.cc",
+            1,
+            binary_file.path(),
+            2,
+        );
+
+        let mut report = vec![];
+        dump_confirmed_leaks(
+            &mut report,
+            vec![ConfirmedLeakWithUniqueLocation::from(leak)],
+            &ReportOptions {
+                format: ReportFormat::Json,
+                context_size: 4,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let report: serde_json::Value = serde_json::from_slice(&report).unwrap();
+        let context = &report["leaks"][0]["context"];
+        assert_eq!(context["offset"], 0);
+        assert_eq!(context["data"], "01027365637265740003");
+        assert_eq!(report["leaks"][0]["data"], "secret");
+        assert_eq!(report["summary"]["leak_count"], 1);
+        assert_eq!(report["summary"]["per_data_type"]["StringLiteral"], 1);
+    }
+
+    #[test]
+    fn text_report_grouped_by_type() {
+        let mut report = vec![];
+        dump_confirmed_leaks(
+            &mut report,
+            vec![
+                ConfirmedLeakWithUniqueLocation::from(ConfirmedLeak::new(
+                    LeakedDataType::StringLiteral,
+                    "secret",
+                    "main.cc",
+                    1,
+                    "a.out",
+                    0x10,
+                )),
+                ConfirmedLeakWithUniqueLocation::from(ConfirmedLeak::new(
+                    LeakedDataType::ClassName,
+                    "MyClass",
+                    "main.cc",
+                    1,
+                    "a.out",
+                    0x20,
+                )),
+                ConfirmedLeakWithUniqueLocation::from(ConfirmedLeak::new(
+                    LeakedDataType::StringLiteral,
+                    "other",
+                    "main.cc",
+                    1,
+                    "a.out",
+                    0x30,
+                )),
+            ],
+            &ReportOptions {
+                group_by: Some(LeakGrouping::Type),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(report).unwrap(),
+            "class name (1 leak(s)):\n  \
+             \"MyClass\" (class name) leaked at offset 0x20 in \"a.out\" [declared at main.cc:1]\n\
+             string literal (2 leak(s)):\n  \
+             \"secret\" (string literal) leaked at offset 0x10 in \"a.out\" [declared at main.cc:1]\n  \
+             \"other\" (string literal) leaked at offset 0x30 in \"a.out\" [declared at main.cc:1]\n"
+        );
+    }
+
+    #[test]
+    fn text_report_grouped_by_binary() {
+        let mut report = vec![];
+        dump_confirmed_leaks(
+            &mut report,
+            vec![
+                ConfirmedLeakWithUniqueLocation::from(ConfirmedLeak::new(
+                    LeakedDataType::StringLiteral,
+                    "secret",
+                    "main.cc",
+                    1,
+                    "a.out",
+                    0x10,
+                )),
+                ConfirmedLeakWithUniqueLocation::from(ConfirmedLeak::new(
+                    LeakedDataType::StringLiteral,
+                    "secret",
+                    "main.cc",
+                    1,
+                    "libb.so",
+                    0x20,
+                )),
+                ConfirmedLeakWithUniqueLocation::from(ConfirmedLeak::new(
+                    LeakedDataType::StringLiteral,
+                    "other",
+                    "main.cc",
+                    1,
+                    "a.out",
+                    0x30,
+                )),
+            ],
+            &ReportOptions {
+                group_by: Some(LeakGrouping::Binary),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            String::from_utf8(report).unwrap(),
+            "a.out (2 leak(s)):\n  \
+             \"secret\" (string literal) leaked at offset 0x10 in \"a.out\" [declared at main.cc:1]\n  \
+             \"other\" (string literal) leaked at offset 0x30 in \"a.out\" [declared at main.cc:1]\n\
+             libb.so (1 leak(s)):\n  \
+             \"secret\" (string literal) leaked at offset 0x20 in \"libb.so\" [declared at main.cc:1]\n"
+        );
+    }
+
+    #[test]
+    fn underlined_hex_dump_spans_lines() {
+        let data: Vec<u8> = (0x40..0x80).collect();
+        let expected = [
+            "00000010  50 51 52 53 54 55 56 57 58 59 5a 5b 5c 5d 5e 5f  |PQRSTUVWXYZ[\\]^_|",
+            "                                                    ^^ ^^",
+            "00000020  60 61 62 63                                      |`abc|",
+            "          ^^ ^^",
+        ];
+        assert_eq!(
+            underlined_hex_dump(&data, 0x1e..0x22, 2),
+            expected.join("\n") + "\n"
+        );
+    }
+
+    #[test]
+    fn context_covers_whole_file_with_maximum_size() {
+        let data = b"\x01\x02secret\x00\x03";
+        let context = binary_context(data, &BinaryLocation::new("a.out", 2, 6), u64::MAX);
+        assert_eq!(context.offset, 0);
+        assert_eq!(context.data, "01027365637265740003");
+        assert_eq!(hex_dump_lines(10, &(2..8), u64::MAX), vec![0..10]);
+    }
+
+    #[test]
+    fn read_json_report_version_mismatch() {
+        let report_file = tempfile::NamedTempFile::new().unwrap();
+        fs::write(
+            report_file.path(),
+            r#"{"version":{"executable":"9.0.0","format":999},"leaks":[]}"#,
+        )
+        .unwrap();
+
+        assert!(read_json_report(report_file.path()).is_err());
+    }
+
+    #[test]
+    fn read_older_json_report() {
+        let report_file = tempfile::NamedTempFile::new().unwrap();
+        fs::write(
+            report_file.path(),
+            r#"{"version":{"executable":"0.1.0","format":1},"leaks":[{"data_type":"StringLiteral","data":"secret","location":{"source":{"file":"main.cc","line":3},"binary":{"file":"a.out","offset":16}}}]}"#,
+        )
+        .unwrap();
+
+        let leaks = read_json_report(report_file.path()).unwrap();
+        assert_eq!(leaks.len(), 1);
+        assert_eq!(*leaks[0].data, "secret");
+        assert_eq!(leaks[0].location.binary.offset, 16);
+        assert_eq!(leaks[0].location.binary.size, 0);
+        assert!(leaks[0].location.binary.form.is_plain());
+    }
+}
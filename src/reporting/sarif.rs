@@ -0,0 +1,479 @@
+use std::path::{Component, Path};
+
+use anyhow::Result;
+use serde::Serialize;
+
+use super::{
+    display_binary_location_details, display_leaked_data_type, SourceFileCache, PKG_VERSION,
+};
+use crate::information_leak::{ColumnRange, ConfirmedLeak, LeakedDataType};
+
+const SARIF_SCHEMA_URI: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+const TOOL_NAME: &str = "cpplumber";
+const TOOL_INFORMATION_URI: &str = "https://github.com/ergrelet/cpplumber";
+/// Unit of the columns in the report (SARIF's default)
+const COLUMN_KIND: &str = "utf16CodeUnits";
+/// Data types reported by the tool, in the order of the rules listed in the
+/// report (see `rule_index`)
+const RULE_DATA_TYPES: [LeakedDataType; 8] = [
+    LeakedDataType::StringLiteral,
+    LeakedDataType::StructName,
+    LeakedDataType::ClassName,
+    LeakedDataType::FunctionName,
+    LeakedDataType::EnumName,
+    LeakedDataType::EnumConstantName,
+    LeakedDataType::VariableName,
+    LeakedDataType::NamespaceName,
+];
+
+#[derive(Serialize)]
+struct SarifLog {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: Vec<Run>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Run {
+    tool: Tool,
+    results: Vec<SarifResult>,
+    column_kind: &'static str,
+}
+
+#[derive(Serialize)]
+struct Tool {
+    driver: ToolComponent,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ToolComponent {
+    name: &'static str,
+    version: &'static str,
+    information_uri: &'static str,
+    rules: Vec<ReportingDescriptor>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReportingDescriptor {
+    id: &'static str,
+    name: &'static str,
+    short_description: Message,
+    default_configuration: ReportingConfiguration,
+}
+
+#[derive(Serialize)]
+struct ReportingConfiguration {
+    level: &'static str,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    rule_id: &'static str,
+    rule_index: usize,
+    level: &'static str,
+    message: Message,
+    locations: Vec<Location>,
+    related_locations: Vec<Location>,
+}
+
+#[derive(Serialize)]
+struct Message {
+    text: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Location {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<usize>,
+    physical_location: PhysicalLocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<Message>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PhysicalLocation {
+    artifact_location: ArtifactLocation,
+    region: Region,
+}
+
+#[derive(Serialize)]
+struct ArtifactLocation {
+    uri: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Region {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start_line: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start_column: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    end_column: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    byte_offset: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    byte_length: Option<u64>,
+}
+
+/// Generates a SARIF 2.1.0 log containing a single run. Each leak is reported
+/// as a result of the rule associated with its data type, located at its
+/// declaration in the source code. The location of the leaked data in the
+/// binary file is given as a related location.
+///
+/// Columns are converted to UTF-16 code units, which requires reading the
+/// source files. Columns are omitted for files that cannot be read.
+pub fn dump_confirmed_leaks_as_sarif<W, SortedConfirmedLeak>(
+    writer: W,
+    confirmed_leaks: Vec<SortedConfirmedLeak>,
+) -> Result<()>
+where
+    W: std::io::Write,
+    SortedConfirmedLeak: Into<ConfirmedLeak>,
+{
+    let rules = RULE_DATA_TYPES
+        .iter()
+        .map(|&data_type| ReportingDescriptor {
+            id: rule_id(data_type),
+            name: rule_name(data_type),
+            short_description: Message {
+                text: format!("Leaked {}", display_leaked_data_type(data_type)),
+            },
+            default_configuration: ReportingConfiguration { level: "warning" },
+        })
+        .collect();
+
+    let mut source_files = SourceFileCache::default();
+    let results = confirmed_leaks
+        .into_iter()
+        .map(|leak| {
+            let leak: ConfirmedLeak = leak.into();
+            let binary_file_uri = path_to_uri(&leak.location.binary.file);
+            let source = &leak.location.source;
+            let columns = source.columns.and_then(|columns| {
+                utf16_column_range(source_files.get(&source.file)?, source.line, columns)
+            });
+            SarifResult {
+                rule_id: rule_id(leak.data_type),
+                rule_index: rule_index(leak.data_type),
+                level: "warning",
+                message: Message {
+                    text: format!(
+                        "\"{}\" ({}) leaked at offset 0x{:x} in \"{}\"",
+                        leak.data,
+                        display_leaked_data_type(leak.data_type),
+                        leak.location.binary.offset,
+                        leak.location.binary.file.display(),
+                    ),
+                },
+                locations: vec![Location {
+                    id: None,
+                    physical_location: PhysicalLocation {
+                        artifact_location: ArtifactLocation {
+                            uri: path_to_uri(&leak.location.source.file),
+                        },
+                        region: Region {
+                            start_line: Some(leak.location.source.line),
+                            start_column: columns.map(|c| c.start),
+                            end_column: columns.map(|c| c.end),
+                            byte_offset: None,
+                            byte_length: None,
+                        },
+                    },
+                    message: None,
+                }],
+                related_locations: vec![Location {
+                    id: Some(0),
+                    physical_location: PhysicalLocation {
+                        artifact_location: ArtifactLocation {
+                            uri: binary_file_uri,
+                        },
+                        region: Region {
+                            start_line: None,
+                            start_column: None,
+                            end_column: None,
+                            byte_offset: Some(leak.location.binary.offset),
+                            byte_length: Some(leak.location.binary.size),
+                        },
+                    },
+                    message: Some(Message {
+                        text: format!(
+                            "Leaked data{}",
+                            display_binary_location_details(&leak.location.binary)
+                        ),
+                    }),
+                }],
+            }
+        })
+        .collect();
+
+    let log = SarifLog {
+        schema: SARIF_SCHEMA_URI,
+        version: SARIF_VERSION,
+        runs: vec![Run {
+            tool: Tool {
+                driver: ToolComponent {
+                    name: TOOL_NAME,
+                    version: PKG_VERSION,
+                    information_uri: TOOL_INFORMATION_URI,
+                    rules,
+                },
+            },
+            results,
+            column_kind: COLUMN_KIND,
+        }],
+    };
+
+    Ok(serde_json::to_writer(writer, &log)?)
+}
+
+/// Converts a range of byte columns on the given line (1-based) of `lines` to
+/// UTF-16 code units. Returns `None` if the line doesn't exist or if the
+/// columns aren't located on character boundaries.
+fn utf16_column_range(lines: &[String], line: u64, columns: ColumnRange) -> Option<ColumnRange> {
+    let content = lines.get((line as usize).checked_sub(1)?)?;
+    let utf16_column = |column: u64| {
+        let prefix = content.get(..(column as usize).checked_sub(1)?)?;
+        Some(prefix.encode_utf16().count() as u64 + 1)
+    };
+
+    Some(ColumnRange {
+        start: utf16_column(columns.start)?,
+        end: utf16_column(columns.end)?,
+    })
+}
+
+/// Returns the index of the rule associated with `data_type` in
+/// `RULE_DATA_TYPES`
+fn rule_index(data_type: LeakedDataType) -> usize {
+    match data_type {
+        LeakedDataType::StringLiteral => 0,
+        LeakedDataType::StructName => 1,
+        LeakedDataType::ClassName => 2,
+        LeakedDataType::FunctionName => 3,
+        LeakedDataType::EnumName => 4,
+        LeakedDataType::EnumConstantName => 5,
+        LeakedDataType::VariableName => 6,
+        LeakedDataType::NamespaceName => 7,
+    }
+}
+
+/// Returns the identifier of the rule associated with `data_type`
+fn rule_id(data_type: LeakedDataType) -> &'static str {
+    match data_type {
+        LeakedDataType::StringLiteral => "leaked-string-literal",
+        LeakedDataType::StructName => "leaked-struct-name",
+        LeakedDataType::ClassName => "leaked-class-name",
+        LeakedDataType::FunctionName => "leaked-function-name",
+        LeakedDataType::EnumName => "leaked-enum-name",
+        LeakedDataType::EnumConstantName => "leaked-enum-constant-name",
+        LeakedDataType::VariableName => "leaked-variable-name",
+        LeakedDataType::NamespaceName => "leaked-namespace-name",
+    }
+}
+
+/// Returns the name of the rule associated with `data_type`
+fn rule_name(data_type: LeakedDataType) -> &'static str {
+    match data_type {
+        LeakedDataType::StringLiteral => "LeakedStringLiteral",
+        LeakedDataType::StructName => "LeakedStructName",
+        LeakedDataType::ClassName => "LeakedClassName",
+        LeakedDataType::FunctionName => "LeakedFunctionName",
+        LeakedDataType::EnumName => "LeakedEnumName",
+        LeakedDataType::EnumConstantName => "LeakedEnumConstantName",
+        LeakedDataType::VariableName => "LeakedVariableName",
+        LeakedDataType::NamespaceName => "LeakedNamespaceName",
+    }
+}
+
+/// Converts a file path into a URI reference. Absolute paths are converted to
+/// `file` URIs, relative paths are kept relative.
+fn path_to_uri(path: &Path) -> String {
+    let mut uri = String::new();
+    let mut needs_separator = false;
+    for component in path.components() {
+        match component {
+            // Windows drive letters and UNC shares
+            Component::Prefix(prefix) => {
+                let prefix = prefix.as_os_str().to_string_lossy();
+                match prefix.strip_prefix(r"\\") {
+                    Some(share) => uri.push_str(&format!("file://{}", share.replace('\\', "/"))),
+                    None => uri.push_str(&format!("file:///{}", prefix)),
+                }
+                needs_separator = false;
+            }
+            Component::RootDir => {
+                if uri.is_empty() {
+                    uri.push_str("file://");
+                }
+                uri.push('/');
+                needs_separator = false;
+            }
+            component => {
+                if needs_separator {
+                    uri.push('/');
+                }
+                percent_encode_into(&component.as_os_str().to_string_lossy(), &mut uri);
+                needs_separator = true;
+            }
+        }
+    }
+
+    uri
+}
+
+/// Appends `segment` to `uri`, percent-encoding characters that aren't allowed
+/// in URI path segments
+fn percent_encode_into(segment: &str, uri: &mut String) {
+    for byte in segment.bytes() {
+        match byte {
+            b'A'..=b'Z'
+            | b'a'..=b'z'
+            | b'0'..=b'9'
+            | b'-'
+            | b'.'
+            | b'_'
+            | b'~'
+            | b'!'
+            | b'$'
+            | b'&'
+            | b'\''
+            | b'('
+            | b')'
+            | b'*'
+            | b'+'
+            | b','
+            | b';'
+            | b'='
+            | b':'
+            | b'@' => uri.push(byte as char),
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::PathBuf, sync::Arc};
+
+    use jsonschema::{Draft, JSONSchema};
+
+    use super::*;
+
+    /// Subset of the official schema, which only contains the objects generated
+    /// by cpplumber
+    const SARIF_SCHEMA_PATH: &str = "tests/data/reporting/sarif-schema-2.1.0-subset.json";
+
+    #[test]
+    fn sarif_report_matches_schema() {
+        let schema_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(SARIF_SCHEMA_PATH);
+        let schema: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(schema_path).unwrap()).unwrap();
+        let schema = JSONSchema::options()
+            .with_draft(Draft::Draft7)
+            .compile(&schema)
+            .expect("invalid SARIF schema");
+
+        let source_dir = tempfile::tempdir().unwrap();
+        let source_file = source_dir.path().join("my main.cc");
+        std::fs::write(&source_file, "int main() {\n\tputs(\"😂sécret\");\n}\n").unwrap();
+
+        let mut leaks = vec![
+            ConfirmedLeak::new(
+                LeakedDataType::StringLiteral,
+                "😂sécret",
+                &source_file,
+                2,
+                "/build/main.exe",
+                0x10,
+            ),
+            ConfirmedLeak::new(
+                LeakedDataType::ClassName,
+                "MyClass",
+                &source_file,
+                2,
+                "/build/main.exe",
+                0x40,
+            ),
+            // Columns of unreadable files cannot be converted
+            ConfirmedLeak::new(
+                LeakedDataType::ClassName,
+                "MyClass",
+                "/src/my project/main.cc",
+                2,
+                "/build/main.exe",
+                0x80,
+            ),
+        ];
+        for leak in &mut leaks {
+            Arc::get_mut(&mut leak.location.source).unwrap().columns =
+                Some(ColumnRange { start: 7, end: 20 });
+            let binary_location = &mut leak.location.binary;
+            binary_location.section = Some(Arc::new(".rdata".to_string()));
+            binary_location.virtual_address = Some(0x140002000 + binary_location.offset);
+            binary_location.relative_virtual_address = Some(0x2000 + binary_location.offset);
+        }
+
+        let mut report = vec![];
+        dump_confirmed_leaks_as_sarif(&mut report, leaks).unwrap();
+        let report: serde_json::Value = serde_json::from_slice(&report).unwrap();
+
+        if let Err(errors) = schema.validate(&report) {
+            let errors: Vec<String> = errors
+                .map(|error| format!("{} ({})", error, error.instance_path))
+                .collect();
+            panic!("SARIF report doesn't match the schema: {:#?}", errors);
+        }
+
+        assert_eq!(report["runs"][0]["columnKind"], "utf16CodeUnits");
+        let results = &report["runs"][0]["results"];
+        assert_eq!(results.as_array().unwrap().len(), 3);
+        assert_eq!(results[1]["ruleId"], "leaked-class-name");
+        assert_eq!(
+            report["runs"][0]["tool"]["driver"]["rules"]
+                [results[1]["ruleIndex"].as_u64().unwrap() as usize]["id"],
+            "leaked-class-name"
+        );
+        // The string literal spans bytes 7 to 19 of the line, and UTF-16 code
+        // units 7 to 16
+        let location = &results[0]["locations"][0]["physicalLocation"];
+        assert_eq!(location["region"]["startLine"], 2);
+        assert_eq!(location["region"]["startColumn"], 7);
+        assert_eq!(location["region"]["endColumn"], 17);
+        let location = &results[2]["locations"][0]["physicalLocation"];
+        assert_eq!(
+            location["artifactLocation"]["uri"],
+            "file:///src/my%20project/main.cc"
+        );
+        assert_eq!(location["region"]["startLine"], 2);
+        assert!(location["region"].get("startColumn").is_none());
+        let related_location = &results[0]["relatedLocations"][0]["physicalLocation"];
+        assert_eq!(
+            related_location["artifactLocation"]["uri"],
+            "file:///build/main.exe"
+        );
+        assert_eq!(related_location["region"]["byteOffset"], 0x10);
+        assert_eq!(related_location["region"]["byteLength"], 11);
+    }
+
+    #[test]
+    fn rule_indices_match_rules() {
+        for (index, &data_type) in RULE_DATA_TYPES.iter().enumerate() {
+            assert_eq!(rule_index(data_type), index);
+        }
+    }
+
+    #[test]
+    fn path_to_uri_relative() {
+        assert_eq!(path_to_uri(Path::new("src/main.cc")), "src/main.cc");
+        assert_eq!(path_to_uri(Path::new("a#b/c d.h")), "a%23b/c%20d.h");
+    }
+}
@@ -0,0 +1,227 @@
+use std::{cmp::Reverse, collections::BTreeMap, sync::Arc};
+
+use anyhow::Result;
+use serde::Serialize;
+
+use super::display_leaked_data_type;
+use crate::information_leak::{ConfirmedLeak, LeakedDataType};
+
+/// Number of leaked values listed in `ReportSummary::top_offenders`
+const TOP_OFFENDER_COUNT: usize = 10;
+
+/// Statistics on the leaks of a report
+#[derive(Debug, Default, Serialize)]
+pub struct ReportSummary {
+    /// Total number of leaks
+    pub leak_count: usize,
+    /// Total size in bytes of the leaked data
+    pub leaked_bytes: u64,
+    /// Number of leaks per type of data
+    pub per_data_type: BTreeMap<LeakedDataType, usize>,
+    /// Number of leaks per source file the leaked data is declared in
+    pub per_source_file: BTreeMap<String, usize>,
+    /// Number of leaks per binary file
+    pub per_binary_file: BTreeMap<String, usize>,
+    /// Number of leaks per section (leaks located outside of sections aren't
+    /// counted)
+    pub per_section: BTreeMap<String, usize>,
+    /// Values leaked the most times, in decreasing order
+    pub top_offenders: Vec<TopOffender>,
+}
+
+/// Leaked value, and the number of times it has been found
+#[derive(Debug, Serialize)]
+pub struct TopOffender {
+    pub data_type: LeakedDataType,
+    pub data: Arc<String>,
+    pub leak_count: usize,
+}
+
+impl<'l> FromIterator<&'l ConfirmedLeak> for ReportSummary {
+    fn from_iter<I: IntoIterator<Item = &'l ConfirmedLeak>>(leaks: I) -> Self {
+        let mut summary = ReportSummary::default();
+        let mut per_value: BTreeMap<(LeakedDataType, Arc<String>), usize> = BTreeMap::new();
+        for leak in leaks {
+            summary.leak_count += 1;
+            summary.leaked_bytes += leak.location.binary.size;
+            *summary.per_data_type.entry(leak.data_type).or_default() += 1;
+            *summary
+                .per_source_file
+                .entry(leak.location.source.file.display().to_string())
+                .or_default() += 1;
+            *summary
+                .per_binary_file
+                .entry(leak.location.binary.file.display().to_string())
+                .or_default() += 1;
+            if let Some(section) = &leak.location.binary.section {
+                *summary.per_section.entry(section.to_string()).or_default() += 1;
+            }
+            *per_value
+                .entry((leak.data_type, leak.data.clone()))
+                .or_default() += 1;
+        }
+
+        let mut top_offenders: Vec<TopOffender> = per_value
+            .into_iter()
+            .map(|((data_type, data), leak_count)| TopOffender {
+                data_type,
+                data,
+                leak_count,
+            })
+            .collect();
+        // Stable sort, values leaked the same number of times stay sorted by
+        // type and value
+        top_offenders.sort_by_key(|offender| Reverse(offender.leak_count));
+        top_offenders.truncate(TOP_OFFENDER_COUNT);
+        summary.top_offenders = top_offenders;
+
+        summary
+    }
+}
+
+/// Writes a text representation of `ReportSummary`. Counts are listed in
+/// decreasing order.
+pub(super) fn dump_summary_as_text<W: std::io::Write>(
+    mut writer: W,
+    summary: &ReportSummary,
+) -> Result<()> {
+    writeln!(writer, "Summary:")?;
+    writeln!(
+        writer,
+        "  {} leak(s), {} byte(s) leaked",
+        summary.leak_count, summary.leaked_bytes
+    )?;
+    dump_counts_as_text(
+        &mut writer,
+        "Per data type",
+        summary
+            .per_data_type
+            .iter()
+            .map(|(&data_type, &count)| (display_leaked_data_type(data_type), count)),
+    )?;
+    dump_counts_as_text(
+        &mut writer,
+        "Per source file",
+        summary
+            .per_source_file
+            .iter()
+            .map(|(file, &count)| (file.clone(), count)),
+    )?;
+    dump_counts_as_text(
+        &mut writer,
+        "Per binary file",
+        summary
+            .per_binary_file
+            .iter()
+            .map(|(file, &count)| (file.clone(), count)),
+    )?;
+    dump_counts_as_text(
+        &mut writer,
+        "Per section",
+        summary
+            .per_section
+            .iter()
+            .map(|(section, &count)| (section.clone(), count)),
+    )?;
+    dump_counts_as_text(
+        &mut writer,
+        "Top offenders",
+        summary.top_offenders.iter().map(|offender| {
+            (
+                format!(
+                    "\"{}\" ({})",
+                    offender.data,
+                    display_leaked_data_type(offender.data_type)
+                ),
+                offender.leak_count,
+            )
+        }),
+    )?;
+
+    Ok(())
+}
+
+/// Writes a titled list of counts, in decreasing order (nothing is written if
+/// the list is empty)
+fn dump_counts_as_text<W: std::io::Write>(
+    mut writer: W,
+    title: &str,
+    counts: impl Iterator<Item = (String, usize)>,
+) -> Result<()> {
+    let mut counts: Vec<(String, usize)> = counts.collect();
+    if counts.is_empty() {
+        return Ok(());
+    }
+    counts.sort_by_key(|&(_, count)| Reverse(count));
+
+    writeln!(writer, "  {}:", title)?;
+    for (name, count) in counts {
+        writeln!(writer, "    {}: {}", name, count)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary_counts_leaks() {
+        let mut leaks = [
+            ConfirmedLeak::new(
+                LeakedDataType::StringLiteral,
+                "secret",
+                "main.cc",
+                1,
+                "a.out",
+                0,
+            ),
+            ConfirmedLeak::new(
+                LeakedDataType::StringLiteral,
+                "secret",
+                "main.cc",
+                1,
+                "a.out",
+                0,
+            ),
+            ConfirmedLeak::new(
+                LeakedDataType::ClassName,
+                "MyClass",
+                "utils.h",
+                1,
+                "a.out",
+                0,
+            ),
+        ];
+        for leak in &mut leaks[1..] {
+            leak.location.binary.section = Some(Arc::new(".rodata".to_string()));
+        }
+        let summary: ReportSummary = leaks.iter().collect();
+
+        assert_eq!(summary.leak_count, 3);
+        assert_eq!(summary.leaked_bytes, 19);
+        assert_eq!(summary.per_data_type[&LeakedDataType::StringLiteral], 2);
+        assert_eq!(summary.per_data_type[&LeakedDataType::ClassName], 1);
+        assert_eq!(summary.per_source_file["main.cc"], 2);
+        assert_eq!(summary.per_binary_file["a.out"], 3);
+        assert_eq!(summary.per_section.len(), 1);
+        assert_eq!(summary.per_section[".rodata"], 2);
+        assert_eq!(summary.top_offenders.len(), 2);
+        assert_eq!(*summary.top_offenders[0].data, "secret");
+        assert_eq!(summary.top_offenders[0].leak_count, 2);
+
+        let mut output = vec![];
+        dump_summary_as_text(&mut output, &summary).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "Summary:\n  \
+             3 leak(s), 19 byte(s) leaked\n  \
+             Per data type:\n    string literal: 2\n    class name: 1\n  \
+             Per source file:\n    main.cc: 2\n    utils.h: 1\n  \
+             Per binary file:\n    a.out: 3\n  \
+             Per section:\n    .rodata: 2\n  \
+             Top offenders:\n    \"secret\" (string literal): 2\n    \"MyClass\" (class name): 1\n"
+        );
+    }
+}
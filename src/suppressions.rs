@@ -4,8 +4,14 @@ use std::path::Path;
 
 use anyhow::Result;
 use glob::Pattern;
+use rayon::prelude::*;
 use serde::Deserialize;
 
+#[cfg(feature = "extraction")]
+use crate::compilation_database::CompileCommands;
+use crate::information_leak::PotentialLeak;
+
+/// Files and artifacts that shouldn't be reported
 pub struct Suppressions {
     pub files: Vec<Pattern>,
     pub artifacts: Vec<String>,
@@ -17,6 +23,19 @@ struct SuppressionsListYaml {
     artifacts: Option<Vec<String>>,
 }
 
+/// Parses a YAML suppressions list. Invalid glob patterns are ignored.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::path::Path;
+///
+/// use cpplumber::suppressions::{filter_suppressed_artifacts_by_value, parse_suppressions_file};
+///
+/// let suppressions = parse_suppressions_file(Path::new("suppressions.yml"))?;
+/// let potential_leaks = filter_suppressed_artifacts_by_value(vec![], &Some(suppressions));
+/// # Ok::<(), anyhow::Error>(())
+/// ```
 pub fn parse_suppressions_file(suppression_file_path: &Path) -> Result<Suppressions> {
     // Read file
     let mut suppression_data = vec![];
@@ -47,11 +66,79 @@ pub fn parse_suppressions_file(suppression_file_path: &Path) -> Result<Suppressi
     })
 }
 
+/// Removes compile commands of suppressed files, to avoid parsing files we're
+/// not interested in.
+#[cfg(feature = "extraction")]
+pub fn filter_suppressed_files(
+    compile_cmds: CompileCommands,
+    suppressions: &Option<Suppressions>,
+) -> CompileCommands {
+    if let Some(suppressions) = suppressions {
+        compile_cmds
+            .into_par_iter()
+            .filter(|compile_cmd| {
+                if let Some(file_path) = compile_cmd.filename.to_str() {
+                    !suppressions
+                        .files
+                        .par_iter()
+                        .any(|pattern| pattern.matches(file_path))
+                } else {
+                    true
+                }
+            })
+            .collect()
+    } else {
+        compile_cmds
+    }
+}
+
+/// Removes artifacts declared in suppressed files.
+pub fn filter_suppressed_artifacts_by_origin(
+    potential_leaks: Vec<PotentialLeak>,
+    suppressions: &Option<Suppressions>,
+) -> Vec<PotentialLeak> {
+    if let Some(suppressions) = suppressions {
+        potential_leaks
+            .into_par_iter()
+            .filter(|leak| {
+                let file_path = &leak.declaration_metadata.file;
+                if let Some(file_path) = file_path.as_os_str().to_str() {
+                    !suppressions
+                        .files
+                        .par_iter()
+                        .any(|pattern| pattern.matches(file_path))
+                } else {
+                    true
+                }
+            })
+            .collect()
+    } else {
+        potential_leaks
+    }
+}
+
+/// Removes suppressed artifacts, based on their value.
+pub fn filter_suppressed_artifacts_by_value(
+    potential_leaks: Vec<PotentialLeak>,
+    suppressions: &Option<Suppressions>,
+) -> Vec<PotentialLeak> {
+    if let Some(suppressions) = suppressions {
+        potential_leaks
+            .into_par_iter()
+            .filter(|leak| !suppressions.artifacts.contains(&leak.data))
+            .collect()
+    } else {
+        potential_leaks
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::path::PathBuf;
+    use std::sync::Arc;
 
     use super::*;
+    use crate::information_leak::{LeakedDataType, SourceLocation};
 
     const FILE1_PATH: &str = "tests/data/suppressions/files_and_artifacts.yml";
 
@@ -73,4 +160,33 @@ mod tests {
         assert_eq!(suppressions.artifacts[0], "c_string");
         assert_eq!(suppressions.artifacts[1], "utf32_string");
     }
+
+    #[test]
+    fn filter_suppressed_artifacts() {
+        let leak = |data: &str, file: &str| PotentialLeak {
+            data_type: LeakedDataType::StringLiteral,
+            data: Arc::new(data.to_string()),
+            bytes: data.as_bytes().to_vec(),
+            declaration_metadata: Arc::new(SourceLocation {
+                file: PathBuf::from(file),
+                line: 1,
+                columns: None,
+            }),
+            alternative_forms: vec![],
+        };
+        let suppressions = Some(Suppressions {
+            files: vec![glob::Pattern::new("*/generated/*").unwrap()],
+            artifacts: vec!["c_string".to_string()],
+        });
+        let potential_leaks = vec![
+            leak("c_string", "src/main.cc"),
+            leak("other_string", "src/generated/table.h"),
+            leak("kept_string", "src/main.cc"),
+        ];
+
+        let potential_leaks = filter_suppressed_artifacts_by_origin(potential_leaks, &suppressions);
+        let potential_leaks = filter_suppressed_artifacts_by_value(potential_leaks, &suppressions);
+        assert_eq!(potential_leaks.len(), 1);
+        assert_eq!(*potential_leaks[0].data, "kept_string");
+    }
 }
@@ -1,6 +1,7 @@
 #include <cstdio>
 #include <memory>
 #include <cwchar>
+#include <typeinfo>
 
 #include "header.h"
 
@@ -43,8 +44,41 @@ class MyClass {
         public:
         int field1;
     };
+
+    int my_method(int my_parameter) const;
+
+    static int my_static_member;
+};
+
+int MyClass::my_method(int my_parameter) const {
+    int my_local_variable = my_parameter;
+    return my_local_variable;
+}
+
+int MyClass::my_static_member = 0;
+
+enum MyEnum {
+    MyEnumConstant,
 };
 
+enum class MyEnumClass {
+    MyEnumClassConstant,
+};
+
+// Unnamed enum
+enum {
+    UnnamedEnumConstant,
+};
+
+namespace my_namespace {
+int my_namespaced_variable = 0;
+}
+
+// Anonymous namespace
+namespace {
+int anonymous_namespace_variable = 0;
+}
+
 int main() {
     printf("%s\n", PREPROCESSOR_STRING_LITERAL);
     wprintf(L"%s\n", PREPROCESSOR_WIDE_STRING_LITERAL);
@@ -53,6 +87,10 @@ int main() {
     // Force the generation of some RTTI
     std::shared_ptr<MyStruct> struct_ptr = std::make_unique<MyStruct>();
     std::shared_ptr<MyClass> class_ptr = std::make_unique<MyClass>();
+    puts(typeid(MyEnum).name());
+    puts(typeid(MyEnumClass).name());
 
-    return 0;
+    // Force the generation of variables with internal linkage
+    static int my_static_local = 0;
+    return my_static_local++ + anonymous_namespace_variable++;
 }